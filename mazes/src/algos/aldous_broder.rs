@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+
+use rand::rngs::SmallRng;
+use rand::seq::IndexedRandom;
+
+use crate::algos::{link, reachable};
+use crate::grid::Grid;
+
+// random walk from a random cell, linking to each neighbour the first time
+// it's visited. unbiased (every spanning tree is equally likely) but slow to
+// finish since the walk keeps crossing cells it's already seen
+pub fn aldous_broder(grid: &mut Box<dyn Grid>, rng: &mut SmallRng) {
+    let mut links = grid.links().clone();
+    let Some(start) = grid.map().choose(rng) else {
+        return;
+    };
+
+    let mut current = start.position;
+    let mut visited = HashSet::from([current]);
+    // the walk can never finish if some cells are cut off
+    let target = reachable(grid.as_ref(), &current).len();
+
+    while visited.len() < target {
+        let cell = grid.cell_at(&current).expect("Couldn't find current cell");
+        let Some(neighbour) = cell.get_neighbours().choose(rng).copied() else {
+            // nowhere to walk to
+            break;
+        };
+
+        if visited.insert(neighbour) {
+            link(&mut links, &current, &neighbour);
+        }
+        current = neighbour;
+    }
+
+    grid.set_links(links);
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        algos::aldous_broder::aldous_broder,
+        grid::{Grid, StandardGrid},
+    };
+
+    #[test]
+    fn should_generate_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
+        aldous_broder(&mut container, &mut rng);
+
+        assert_eq!(
+            format!("{}", container),
+            "+---+---+---+---+
+|   |       |   |
++   +   +---+   +
+|               |
++---+   +---+   +
+|           |   |
++   +   +   +---+
+|   |   |       |
++---+---+---+---+
+"
+        );
+    }
+
+    #[test]
+    fn should_link_every_cell() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(10, 10));
+        aldous_broder(&mut container, &mut rng);
+
+        // a perfect maze has one fewer passage than cells, stored both ways
+        let link_count: usize = container.links().values().map(|links| links.len()).sum();
+        assert_eq!(link_count, 2 * 99);
+        assert_eq!(container.links().len(), 100);
+    }
+}
@@ -0,0 +1,268 @@
+use std::fmt::{self, Display, Formatter};
+
+use rand::rngs::SmallRng;
+use rand::{Rng, seq::SliceRandom};
+
+use crate::{
+    Position,
+    algos::link,
+    base_grid::{CELL_SIZE, WALL_COLOUR, svg_line},
+    grid::Grid,
+};
+
+// one row of an eller's maze
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EllerRow {
+    pub y: i32,
+    // east[x] is a passage from x to x + 1 (the last cell never has one)
+    pub east: Vec<bool>,
+    // south[x] is a passage from x down into the next row
+    pub south: Vec<bool>,
+}
+
+impl EllerRow {
+    pub fn width(&self) -> i32 {
+        self.east.len() as i32
+    }
+
+    // walls for this row, in the same order Svg::draw uses for a whole grid,
+    // so rows can be written out one at a time and wrapped in an <svg> tag
+    pub fn svg(&self) -> String {
+        let mut output = String::new();
+        let y1 = self.y * CELL_SIZE;
+        let y2 = (self.y + 1) * CELL_SIZE;
+
+        for x in 0..self.width() {
+            let x1 = x * CELL_SIZE;
+            let x2 = (x + 1) * CELL_SIZE;
+
+            if self.y == 0 {
+                output += &svg_line(x1, y1, x2, y1, WALL_COLOUR);
+            }
+            if x == 0 {
+                output += &svg_line(x1, y1, x1, y2, WALL_COLOUR);
+            }
+            if !self.east[x as usize] {
+                output += &svg_line(x2, y1, x2, y2, WALL_COLOUR);
+            }
+            if !self.south[x as usize] {
+                output += &svg_line(x1, y2, x2, y2, WALL_COLOUR);
+            }
+        }
+
+        output
+    }
+}
+
+// the same ascii as Display for dyn Grid, a row at a time.
+// the first row also draws the top wall
+impl Display for EllerRow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.y == 0 {
+            writeln!(f, "+{}", "---+".repeat(self.east.len()))?;
+        }
+
+        let mut left = "|".to_owned();
+        let mut bottom = "+".to_owned();
+        for (east, south) in self.east.iter().zip(self.south.iter()) {
+            left += if *east { "    " } else { "   |" };
+            bottom += if *south { "   +" } else { "---+" };
+        }
+
+        writeln!(f, "{}\n{}", left, bottom)
+    }
+}
+
+// streams an eller's maze row by row, only ever holding the current row
+// in memory, so mazes can be as tall as you like. rows from next_row (or the
+// iterator, which never ends) always leave passages down into the row after;
+// call last_row to close the maze off
+pub struct Ellers<'a> {
+    width: usize,
+    y: i32,
+    rng: &'a mut SmallRng,
+    // set id of each cell in the current row
+    sets: Vec<usize>,
+    next_set: usize,
+}
+
+impl<'a> Ellers<'a> {
+    pub fn new(width: i32, rng: &'a mut SmallRng) -> Self {
+        let width = width.max(0) as usize;
+        Ellers {
+            width,
+            y: 0,
+            rng,
+            sets: (0..width).collect(),
+            next_set: width,
+        }
+    }
+
+    pub fn next_row(&mut self) -> EllerRow {
+        self.row(false)
+    }
+
+    // joins everything still apart, with no passages down
+    pub fn last_row(&mut self) -> EllerRow {
+        self.row(true)
+    }
+
+    fn row(&mut self, last: bool) -> EllerRow {
+        let mut east = vec![false; self.width];
+        let mut south = vec![false; self.width];
+
+        // randomly join neighbours that aren't connected yet
+        for (x, passage) in east
+            .iter_mut()
+            .enumerate()
+            .take(self.width.saturating_sub(1))
+        {
+            let (set, other) = (self.sets[x], self.sets[x + 1]);
+            if set != other && (last || self.rng.random_bool(0.5)) {
+                *passage = true;
+                self.sets
+                    .iter_mut()
+                    .filter(|id| **id == other)
+                    .for_each(|id| *id = set);
+            }
+        }
+
+        if !last {
+            // every set needs at least one passage down or it'd be cut off
+            let mut seen = vec![];
+            for set in self.sets.iter() {
+                if !seen.contains(set) {
+                    seen.push(*set);
+                }
+            }
+
+            for set in seen {
+                let mut members: Vec<_> =
+                    (0..self.width).filter(|x| self.sets[*x] == set).collect();
+                members.shuffle(self.rng);
+                for (index, x) in members.into_iter().enumerate() {
+                    if index == 0 || self.rng.random_ratio(1, 3) {
+                        south[x] = true;
+                    }
+                }
+            }
+
+            // cells without a passage down from above start new sets
+            for (x, down) in south.iter().enumerate() {
+                if !down {
+                    self.sets[x] = self.next_set;
+                    self.next_set += 1;
+                }
+            }
+        }
+
+        let row = EllerRow {
+            y: self.y,
+            east,
+            south,
+        };
+        self.y += 1;
+        row
+    }
+}
+
+impl Iterator for Ellers<'_> {
+    type Item = EllerRow;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_row())
+    }
+}
+
+// eller's for a whole grid, feeding each streamed row into the links
+pub fn ellers(grid: &mut Box<dyn Grid>, rng: &mut SmallRng) {
+    let mut links = grid.links().clone();
+    let height = grid.height();
+    let mut rows = Ellers::new(grid.width(), rng);
+
+    for y in 0..height {
+        let row = if y == height - 1 {
+            rows.last_row()
+        } else {
+            rows.next_row()
+        };
+
+        for x in 0..row.width() {
+            let pos = Position { x, y };
+            let Some(cell) = grid.cell_at(&pos) else {
+                continue;
+            };
+            let neighbours = cell.get_neighbours();
+
+            let east = Position { x: x + 1, y };
+            if row.east[x as usize] && neighbours.contains(&east) {
+                link(&mut links, &pos, &east);
+            }
+            let south = Position { x, y: y + 1 };
+            if row.south[x as usize] && neighbours.contains(&south) {
+                link(&mut links, &pos, &south);
+            }
+        }
+    }
+
+    grid.set_links(links);
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        algos::ellers::{Ellers, ellers},
+        base_grid::Svg,
+        grid::{Grid, StandardGrid},
+    };
+
+    #[test]
+    fn should_generate_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
+        ellers(&mut container, &mut rng);
+
+        assert_eq!(
+            format!("{}", container),
+            "+---+---+---+---+
+|   |   |   |   |
++   +   +   +   +
+|           |   |
++   +---+   +   +
+|   |           |
++---+   +---+   +
+|           |   |
++---+---+---+---+
+"
+        );
+    }
+
+    #[test]
+    fn should_stream_rows_matching_grid() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
+        ellers(&mut container, &mut rng);
+
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut stream = Ellers::new(4, &mut rng);
+        let mut rows: Vec<_> = stream.by_ref().take(3).collect();
+        rows.push(stream.last_row());
+
+        let ascii: String = rows.iter().map(|row| row.to_string()).collect();
+        assert_eq!(ascii, format!("{}", container));
+
+        let svg: String = rows.iter().map(|row| row.svg()).collect();
+        assert_eq!(
+            format!(
+                "<svg viewBox=\"0 0 64 64\" xmlns=\"http://www.w3.org/2000/svg\">{}</svg>",
+                svg
+            ),
+            container.draw(container.map(), container.width(), container.height())
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use std::{
+    collections::HashSet,
+    error::Error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use rand::rngs::SmallRng;
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::{Position, algos::link, grid::Grid};
+
+// picks which active cell growing tree carries on from.
+// implement this to plug in your own selection rules
+pub trait CellSelector {
+    // index into `active` (which is never empty), oldest cell first
+    fn select(&mut self, active: &[Position], rng: &mut SmallRng) -> usize;
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Strategy {
+    // behaves like the recursive backtracker
+    Newest,
+    Oldest,
+    // behaves like simplified prim's
+    #[default]
+    Random,
+    // each pick uses one of the strategies, chosen in proportion to its weight.
+    // build with `Strategy::mix` to check the weights. a mix with nothing
+    // to pick picks at random
+    Mix(Vec<(Strategy, f64)>),
+}
+
+impl Strategy {
+    // a mix of at least one strategy, each with a positive weight, and
+    // weights small enough to add up without overflowing
+    pub fn mix(strategies: Vec<(Strategy, f64)>) -> Result<Self, ParseStrategyError> {
+        if strategies.is_empty() {
+            return Err(ParseStrategyError(String::from("mix has no strategies")));
+        }
+        if let Some((strategy, weight)) = strategies
+            .iter()
+            .find(|(_, weight)| !weight.is_finite() || *weight <= 0.0)
+        {
+            return Err(ParseStrategyError(format!(
+                "weight must be positive for {:?}, not {}",
+                strategy, weight
+            )));
+        }
+        let total: f64 = strategies.iter().map(|(_, weight)| weight).sum();
+        if !total.is_finite() {
+            return Err(ParseStrategyError(String::from(
+                "weights add up to too much",
+            )));
+        }
+        Ok(Strategy::Mix(strategies))
+    }
+}
+
+impl CellSelector for Strategy {
+    fn select(&mut self, active: &[Position], rng: &mut SmallRng) -> usize {
+        match self {
+            Strategy::Newest => active.len() - 1,
+            Strategy::Oldest => 0,
+            Strategy::Random => rng.random_range(0..active.len()),
+            Strategy::Mix(strategies) => {
+                // weights that aren't positive never get picked. `Strategy::mix`
+                // rejects them, but the variant can be built without it
+                let usable = |weight: &f64| weight.is_finite() && *weight > 0.0;
+                let total: f64 = strategies
+                    .iter()
+                    .map(|(_, weight)| weight)
+                    .filter(|weight| usable(weight))
+                    .sum();
+                if !total.is_finite() || total <= 0.0 {
+                    // nothing to choose between, or no range to pick from
+                    return rng.random_range(0..active.len());
+                }
+
+                let mut pick = rng.random_range(0.0..total);
+                let mut last = None;
+                for (strategy, weight) in strategies.iter_mut() {
+                    if !usable(weight) {
+                        continue;
+                    }
+                    if pick < *weight {
+                        return strategy.select(active, rng);
+                    }
+                    pick -= *weight;
+                    last = Some(strategy);
+                }
+                // only reachable through float rounding
+                match last {
+                    Some(strategy) => strategy.select(active, rng),
+                    None => rng.random_range(0..active.len()),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseStrategyError(String);
+
+impl Display for ParseStrategyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid strategy: {}", self.0)
+    }
+}
+
+impl Error for ParseStrategyError {}
+
+// "newest", "oldest", "random", or weighted mixes like "newest:0.75,random:0.25"
+impl FromStr for Strategy {
+    type Err = ParseStrategyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_name = |name: &str| match name.trim() {
+            "newest" => Ok(Strategy::Newest),
+            "oldest" => Ok(Strategy::Oldest),
+            "random" => Ok(Strategy::Random),
+            other => Err(ParseStrategyError(format!("unknown strategy '{}'", other))),
+        };
+
+        if !s.contains(':') && !s.contains(',') {
+            return parse_name(s);
+        }
+
+        let mut strategies = vec![];
+        for part in s.split(',') {
+            let (name, weight) = part.split_once(':').unwrap_or((part, "1"));
+            let weight: f64 = weight
+                .trim()
+                .parse()
+                .map_err(|_| ParseStrategyError(format!("invalid weight in '{}'", part)))?;
+            strategies.push((parse_name(name)?, weight));
+        }
+
+        Strategy::mix(strategies)
+    }
+}
+
+// keep a list of active cells, repeatedly choosing one to grow a passage from
+// and dropping it once it has no unvisited neighbours. the texture depends
+// entirely on which cell gets chosen
+pub fn growing_tree(grid: &mut Box<dyn Grid>, rng: &mut SmallRng) {
+    growing_tree_with(grid, rng, &mut Strategy::default());
+}
+
+pub fn growing_tree_with(
+    grid: &mut Box<dyn Grid>,
+    rng: &mut SmallRng,
+    selector: &mut dyn CellSelector,
+) {
+    let mut links = grid.links().clone();
+    let Some(start) = grid.map().choose(rng) else {
+        return;
+    };
+
+    let mut active = vec![start.position];
+    let mut visited = HashSet::from([start.position]);
+
+    while !active.is_empty() {
+        let index = selector.select(&active, rng);
+        let current = active[index];
+        let cell = grid.cell_at(&current).expect("Couldn't find current cell");
+        let available: Vec<_> = cell
+            .get_neighbours()
+            .into_iter()
+            .filter(|neighbour| !visited.contains(neighbour))
+            .collect();
+
+        match available.choose(rng) {
+            Some(neighbour) => {
+                link(&mut links, &current, neighbour);
+                visited.insert(*neighbour);
+                active.push(*neighbour);
+            }
+            None => {
+                active.remove(index);
+            }
+        }
+    }
+
+    grid.set_links(links);
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        Position,
+        algos::growing_tree::{Strategy, growing_tree_with},
+        distances::distances,
+        grid::{Grid, StandardGrid},
+    };
+
+    #[test]
+    fn should_generate_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
+        let mut strategy: Strategy = "newest:0.5,random:0.5".parse().unwrap();
+        growing_tree_with(&mut container, &mut rng, &mut strategy);
+
+        assert_eq!(
+            format!("{}", container),
+            "+---+---+---+---+
+|               |
++   +---+---+   +
+|   |       |   |
++---+   +---+   +
+|               |
++   +   +---+   +
+|   |   |       |
++---+---+---+---+
+"
+        );
+    }
+
+    #[test]
+    fn should_parse_strategies() {
+        assert_eq!("newest".parse(), Ok(Strategy::Newest));
+        assert_eq!(
+            "newest:0.75, random:0.25".parse(),
+            Ok(Strategy::Mix(vec![
+                (Strategy::Newest, 0.75),
+                (Strategy::Random, 0.25)
+            ]))
+        );
+        assert!("newest:-1".parse::<Strategy>().is_err());
+        assert!("sideways".parse::<Strategy>().is_err());
+    }
+
+    #[test]
+    fn should_not_panic_on_empty_mixes() {
+        assert!(Strategy::mix(vec![]).is_err());
+        assert!(Strategy::mix(vec![(Strategy::Newest, 0.0)]).is_err());
+        assert!(Strategy::mix(vec![(Strategy::Newest, 1e308), (Strategy::Random, 1e308)]).is_err());
+        assert!("newest:1e308,random:1e308".parse::<Strategy>().is_err());
+
+        // built directly, so nothing checked the weights
+        for mut strategy in [
+            Strategy::Mix(vec![]),
+            Strategy::Mix(vec![(Strategy::Newest, 0.0), (Strategy::Oldest, f64::NAN)]),
+            Strategy::Mix(vec![(Strategy::Newest, 1e308), (Strategy::Oldest, 1e308)]),
+        ] {
+            let seed = "abc12345abc";
+            let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+            let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
+            growing_tree_with(&mut container, &mut rng, &mut strategy);
+            assert_eq!(
+                distances(&Position { x: 0, y: 0 }, container.links()).len(),
+                16
+            );
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+
+use rand::rngs::SmallRng;
+use rand::seq::IndexedRandom;
+
+use crate::algos::link;
+use crate::grid::Grid;
+
+// random walk through unvisited cells until stuck, then hunt through the map
+// in row order for an unvisited cell next to the maze and carry on from there.
+// gives long winding passages with few dead ends
+pub fn hunt_and_kill(grid: &mut Box<dyn Grid>, rng: &mut SmallRng) {
+    let mut links = grid.links().clone();
+    let Some(start) = grid.map().choose(rng) else {
+        return;
+    };
+
+    let mut current = Some(start.position);
+    let mut visited = HashSet::new();
+
+    while let Some(pos) = current {
+        visited.insert(pos);
+        let cell = grid.cell_at(&pos).expect("Couldn't find current cell");
+        let unvisited: Vec<_> = cell
+            .get_neighbours()
+            .into_iter()
+            .filter(|neighbour| !visited.contains(neighbour))
+            .collect();
+
+        // kill
+        if let Some(neighbour) = unvisited.choose(rng) {
+            link(&mut links, &pos, neighbour);
+            current = Some(*neighbour);
+            continue;
+        }
+
+        // hunt
+        current = None;
+        for cell in grid.map() {
+            if visited.contains(&cell.position) {
+                continue;
+            }
+
+            let visited_neighbours: Vec<_> = cell
+                .get_neighbours()
+                .into_iter()
+                .filter(|neighbour| visited.contains(neighbour))
+                .collect();
+
+            if let Some(neighbour) = visited_neighbours.choose(rng) {
+                link(&mut links, &cell.position, neighbour);
+                current = Some(cell.position);
+                break;
+            }
+        }
+    }
+
+    grid.set_links(links);
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        algos::hunt_and_kill::hunt_and_kill,
+        grid::{Grid, StandardGrid},
+    };
+
+    #[test]
+    fn should_generate_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
+        hunt_and_kill(&mut container, &mut rng);
+
+        assert_eq!(
+            format!("{}", container),
+            "+---+---+---+---+
+|   |       |   |
++   +   +   +   +
+|       |       |
++---+---+---+   +
+|       |       |
++---+   +   +---+
+|               |
++---+---+---+---+
+"
+        );
+    }
+}
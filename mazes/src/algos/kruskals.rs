@@ -0,0 +1,183 @@
+use std::{
+    collections::HashSet,
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+
+use crate::{Position, algos::link, disjoint_set::DisjointSet, grid::Grid};
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum ForcedLinkError {
+    // the cells aren't next to each other (or aren't in the grid)
+    NotNeighbours(Position, Position),
+    // the cells are already connected, so linking them would make a loop
+    WouldLoop(Position, Position),
+}
+
+impl Display for ForcedLinkError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ForcedLinkError::NotNeighbours(a, b) => {
+                write!(f, "{:?} and {:?} aren't neighbours", a, b)
+            }
+            ForcedLinkError::WouldLoop(a, b) => {
+                write!(f, "linking {:?} and {:?} would create a loop", a, b)
+            }
+        }
+    }
+}
+
+impl Error for ForcedLinkError {}
+
+// shuffle every wall between neighbours, then knock each one down
+// if the cells either side aren't already connected
+pub fn kruskals(grid: &mut Box<dyn Grid>, rng: &mut SmallRng) {
+    kruskals_with_links(grid, rng, &[]).expect("Failed to generate maze");
+}
+
+// as kruskals, but links each pair in `forced` before the random merging
+// starts, so the maze is guaranteed to include them. any links already on the
+// grid are kept too. the grid is left untouched if a forced link is invalid
+pub fn kruskals_with_links(
+    grid: &mut Box<dyn Grid>,
+    rng: &mut SmallRng,
+    forced: &[(Position, Position)],
+) -> Result<(), ForcedLinkError> {
+    let mut links = grid.links().clone();
+    let mut sets = DisjointSet::new();
+    for cell in grid.map() {
+        sets.add(cell.position);
+    }
+
+    for (start, neighbours) in grid.links() {
+        for neighbour in neighbours {
+            sets.union(*start, *neighbour);
+        }
+    }
+
+    for (start, neighbour) in forced {
+        let is_neighbour = grid
+            .cell_at(start)
+            .is_some_and(|cell| cell.neighbours.values().any(|pos| pos == neighbour));
+        if !is_neighbour {
+            return Err(ForcedLinkError::NotNeighbours(*start, *neighbour));
+        }
+
+        if !sets.union(*start, *neighbour) {
+            return Err(ForcedLinkError::WouldLoop(*start, *neighbour));
+        }
+        link(&mut links, start, neighbour);
+    }
+
+    // each wall once, in map order so the shuffle is repeatable
+    let mut walls = vec![];
+    let mut seen = HashSet::new();
+    for cell in grid.map() {
+        seen.insert(cell.position);
+        for neighbour in cell.get_neighbours() {
+            if !seen.contains(&neighbour) {
+                walls.push((cell.position, neighbour));
+            }
+        }
+    }
+    walls.shuffle(rng);
+
+    for (start, neighbour) in walls {
+        if sets.union(start, neighbour) {
+            link(&mut links, &start, &neighbour);
+        }
+    }
+
+    grid.set_links(links);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        Position,
+        algos::kruskals::{ForcedLinkError, kruskals, kruskals_with_links},
+        grid::{Grid, StandardGrid},
+    };
+
+    #[test]
+    fn should_generate_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
+        kruskals(&mut container, &mut rng);
+
+        assert_eq!(
+            format!("{}", container),
+            "+---+---+---+---+
+|       |   |   |
++   +   +   +   +
+|   |   |       |
++   +---+   +   +
+|           |   |
++   +   +---+---+
+|   |           |
++---+---+---+---+
+"
+        );
+    }
+
+    #[test]
+    fn should_keep_forced_links() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
+
+        // corridor along the second row
+        let forced = [
+            (Position { x: 0, y: 1 }, Position { x: 1, y: 1 }),
+            (Position { x: 1, y: 1 }, Position { x: 2, y: 1 }),
+            (Position { x: 2, y: 1 }, Position { x: 3, y: 1 }),
+        ];
+        kruskals_with_links(&mut container, &mut rng, &forced).unwrap();
+
+        assert_eq!(
+            format!("{}", container),
+            "+---+---+---+---+
+|       |   |   |
++   +---+   +   +
+|               |
++   +---+---+   +
+|           |   |
++   +   +---+---+
+|   |           |
++---+---+---+---+
+"
+        );
+        let link_count: usize = container.links().values().map(|links| links.len()).sum();
+        assert_eq!(link_count, 2 * 15);
+    }
+
+    #[test]
+    fn should_reject_invalid_forced_links() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
+
+        let a = Position { x: 0, y: 0 };
+        let b = Position { x: 1, y: 0 };
+        let c = Position { x: 1, y: 1 };
+        let d = Position { x: 0, y: 1 };
+
+        assert_eq!(
+            kruskals_with_links(&mut container, &mut rng, &[(a, c)]),
+            Err(ForcedLinkError::NotNeighbours(a, c))
+        );
+        assert_eq!(
+            kruskals_with_links(&mut container, &mut rng, &[(a, b), (b, c), (c, d), (d, a)]),
+            Err(ForcedLinkError::WouldLoop(d, a))
+        );
+        assert!(container.links().is_empty());
+    }
+}
@@ -1,2 +1,382 @@
+use std::collections::{HashMap, HashSet};
+
+use rand::rngs::SmallRng;
+
+use crate::{Position, Shape, Topology, algos::growing_tree::Strategy, grid::Grid};
+
+pub mod aldous_broder;
 pub mod binary_tree;
+pub mod ellers;
+pub mod growing_tree;
+pub mod hunt_and_kill;
+pub mod kruskals;
+pub mod prims;
+pub mod recursive_backtracker;
+pub mod recursive_division;
 pub mod sidewinder;
+pub mod wilsons;
+
+pub type GenerateFn = fn(&mut Box<dyn Grid>, &mut SmallRng);
+pub type GenerateWithFn = fn(&mut Box<dyn Grid>, &mut SmallRng, &Options);
+
+// settings for the generators that take them. each generator ignores the
+// ones it doesn't use
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Options {
+    // cell selection for growing-tree
+    pub strategy: Option<Strategy>,
+    // largest room recursive-division may leave open
+    pub room_size: Option<i32>,
+}
+
+// only step south and east through rows and columns
+const SQUARE: &[Shape] = &[Shape::Square];
+// anything with neighbours, except weave grids: those come with passages
+// already carved, and only kruskals counts them as joining their cells
+const ANY_SHAPE: &[Shape] = &[
+    Shape::Square,
+    Shape::Polar,
+    Shape::Hex,
+    Shape::Triangle,
+    Shape::Levels,
+    Shape::Upsilon,
+    Shape::Graph,
+];
+const ANY_SHAPE_OR_WEAVE: &[Shape] = &[
+    Shape::Square,
+    Shape::Polar,
+    Shape::Hex,
+    Shape::Triangle,
+    Shape::Weave,
+    Shape::Levels,
+    Shape::Upsilon,
+    Shape::Graph,
+];
+
+// a named maze generator, so callers can list them and pick one by name
+#[derive(Debug, Clone, Copy)]
+pub struct Generator {
+    pub name: &'static str,
+    pub description: &'static str,
+    // the texture or skew the algorithm leaves in its mazes
+    pub bias: &'static str,
+    pub generate: GenerateFn,
+    // used instead of `generate` by generators that take options
+    pub with_options: Option<GenerateWithFn>,
+    // grid shapes it makes a proper maze on
+    pub shapes: &'static [Shape],
+    // walks rows or splits rectangles, so it needs every cell of the
+    // rectangle there. cells left out by a mask would leave others cut off
+    pub rectangle_only: bool,
+    // only ever carves towards the south and east edges, so on a grid whose
+    // edges join up it carves loops across them
+    pub plane_only: bool,
+}
+
+impl Generator {
+    pub fn generate_with(&self, grid: &mut Box<dyn Grid>, rng: &mut SmallRng, options: &Options) {
+        match self.with_options {
+            Some(generate) => generate(grid, rng, options),
+            None => (self.generate)(grid, rng),
+        }
+    }
+
+    pub fn supports(&self, grid: &dyn Grid) -> bool {
+        self.shapes.contains(&grid.shape())
+            && !(self.rectangle_only && grid.masked())
+            && !(self.plane_only && grid.topology() != Topology::Plane)
+    }
+}
+
+fn growing_tree_with_options(grid: &mut Box<dyn Grid>, rng: &mut SmallRng, options: &Options) {
+    let mut strategy = options.strategy.clone().unwrap_or_default();
+    growing_tree::growing_tree_with(grid, rng, &mut strategy);
+}
+
+fn recursive_division_with_options(
+    grid: &mut Box<dyn Grid>,
+    rng: &mut SmallRng,
+    options: &Options,
+) {
+    recursive_division::recursive_division_with(grid, rng, options.room_size);
+}
+
+pub const GENERATORS: &[Generator] = &[
+    Generator {
+        name: "binary",
+        description: "Binary tree: links each cell south or east at random",
+        bias: "Open corridors along the south row and east column, diagonal texture",
+        generate: binary_tree::binary_tree,
+        with_options: None,
+        shapes: SQUARE,
+        rectangle_only: true,
+        plane_only: true,
+    },
+    Generator {
+        name: "sidewinder",
+        description: "Sidewinder: runs east along each row, closing each run with a link south",
+        bias: "Open corridor along the south row, vertical texture",
+        generate: sidewinder::sidewinder,
+        with_options: None,
+        shapes: SQUARE,
+        rectangle_only: true,
+        plane_only: true,
+    },
+    Generator {
+        name: "aldous-broder",
+        description: "Aldous-Broder: random walk, linking cells on their first visit",
+        bias: "None (uniform spanning tree), but slow to finish",
+        generate: aldous_broder::aldous_broder,
+        with_options: None,
+        shapes: ANY_SHAPE,
+        rectangle_only: false,
+        plane_only: false,
+    },
+    Generator {
+        name: "wilsons",
+        description: "Wilson's: loop-erased random walks into the maze",
+        bias: "None (uniform spanning tree), but slow to start",
+        generate: wilsons::wilsons,
+        with_options: None,
+        shapes: ANY_SHAPE,
+        rectangle_only: false,
+        plane_only: false,
+    },
+    Generator {
+        name: "hunt-and-kill",
+        description: "Hunt-and-kill: random walk until stuck, then hunt for a new start",
+        bias: "Long winding passages, few dead ends",
+        generate: hunt_and_kill::hunt_and_kill,
+        with_options: None,
+        shapes: ANY_SHAPE,
+        rectangle_only: false,
+        plane_only: false,
+    },
+    Generator {
+        name: "recursive-backtracker",
+        description: "Recursive backtracker: depth-first search with an explicit stack",
+        bias: "Long winding passages, very few dead ends",
+        generate: recursive_backtracker::recursive_backtracker,
+        with_options: None,
+        shapes: ANY_SHAPE,
+        rectangle_only: false,
+        plane_only: false,
+    },
+    Generator {
+        name: "kruskals",
+        description: "Kruskal's: removes random walls between unconnected areas",
+        bias: "Lots of short dead ends",
+        generate: kruskals::kruskals,
+        with_options: None,
+        shapes: ANY_SHAPE_OR_WEAVE,
+        rectangle_only: false,
+        plane_only: false,
+    },
+    Generator {
+        name: "simplified-prims",
+        description: "Simplified Prim's: grows outwards from random edge cells",
+        bias: "Radial texture, lots of short dead ends",
+        generate: prims::simplified_prims,
+        with_options: None,
+        shapes: ANY_SHAPE,
+        rectangle_only: false,
+        plane_only: false,
+    },
+    Generator {
+        name: "true-prims",
+        description: "True Prim's: grows outwards from the cheapest of randomly weighted cells",
+        bias: "Radial texture, lots of short dead ends",
+        generate: prims::true_prims,
+        with_options: None,
+        shapes: ANY_SHAPE,
+        rectangle_only: false,
+        plane_only: false,
+    },
+    Generator {
+        name: "growing-tree",
+        description: "Growing tree: grows from active cells picked by a selection strategy",
+        bias: "Depends on the strategy, from backtracker to Prim's",
+        generate: growing_tree::growing_tree,
+        with_options: Some(growing_tree_with_options),
+        shapes: ANY_SHAPE,
+        rectangle_only: false,
+        plane_only: false,
+    },
+    Generator {
+        name: "ellers",
+        description: "Eller's: builds the maze a row at a time",
+        bias: "Slight horizontal texture",
+        generate: ellers::ellers,
+        with_options: None,
+        shapes: SQUARE,
+        rectangle_only: true,
+        plane_only: true,
+    },
+    Generator {
+        name: "recursive-division",
+        description: "Recursive division: adds walls to an open grid, splitting it in two",
+        bias: "Long straight walls, boxy texture",
+        generate: recursive_division::recursive_division,
+        with_options: Some(recursive_division_with_options),
+        shapes: SQUARE,
+        rectangle_only: true,
+        plane_only: false,
+    },
+];
+
+pub fn find(name: &str) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|generator| generator.name == name)
+}
+
+// every cell that can be reached from start by walking between neighbours.
+// on masked grids some cells may be cut off from the rest
+pub(crate) fn reachable(grid: &dyn Grid, start: &Position) -> HashSet<Position> {
+    let mut found = HashSet::from([*start]);
+    let mut frontier = vec![*start];
+
+    while let Some(pos) = frontier.pop() {
+        if let Some(cell) = grid.cell_at(&pos) {
+            for neighbour in cell.get_neighbours() {
+                if found.insert(neighbour) {
+                    frontier.push(neighbour);
+                }
+            }
+        }
+    }
+
+    found
+}
+
+pub(crate) fn link(
+    links: &mut HashMap<Position, Vec<Position>>,
+    start: &Position,
+    neighbour: &Position,
+) {
+    // bi-directional. linking cells that are already linked does nothing
+    for (from, to) in [(start, neighbour), (neighbour, start)] {
+        let cell_links = links.entry(*from).or_insert(vec![]);
+        if !cell_links.contains(to) {
+            cell_links.push(*to);
+        }
+    }
+}
+
+pub(crate) fn unlink(
+    links: &mut HashMap<Position, Vec<Position>>,
+    start: &Position,
+    neighbour: &Position,
+) {
+    if let Some(cell_links) = links.get_mut(start) {
+        cell_links.retain(|pos| pos != neighbour);
+    }
+    if let Some(cell_links) = links.get_mut(neighbour) {
+        cell_links.retain(|pos| pos != start);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        Shape, Topology,
+        algos::{GENERATORS, Options, find, recursive_division::recursive_division_with},
+        distances::distances,
+        grid::{Grid, StandardGrid},
+        polar_grid::PolarGrid,
+        weave_grid::WeaveGrid,
+    };
+
+    #[test]
+    fn it_should_find_generators_by_name() {
+        assert_eq!(
+            find("wilsons").map(|generator| generator.name),
+            Some("wilsons")
+        );
+        assert!(find("nope").is_none());
+    }
+
+    #[test]
+    fn it_should_pass_options_to_generators() {
+        let seed = "abc12345abc";
+        let options = Options {
+            room_size: Some(4),
+            ..Options::default()
+        };
+
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut grid: Box<dyn Grid> = Box::new(StandardGrid::new(8, 8));
+        find("recursive-division")
+            .unwrap()
+            .generate_with(&mut grid, &mut rng, &options);
+
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut expected: Box<dyn Grid> = Box::new(StandardGrid::new(8, 8));
+        recursive_division_with(&mut expected, &mut rng, Some(4));
+        assert_eq!(grid.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn it_should_know_which_shapes_generators_support() {
+        let binary = find("binary").unwrap();
+        assert!(binary.supports(&StandardGrid::new(4, 4)));
+        assert!(!binary.supports(&PolarGrid::new(4)));
+
+        // carving south and east loops round wrapped edges
+        let torus = StandardGrid::with_topology(4, 4, Topology::Torus);
+        let wrapped: Vec<&str> = GENERATORS
+            .iter()
+            .filter(|generator| generator.shapes.contains(&Shape::Square))
+            .filter(|generator| !generator.supports(&torus))
+            .map(|generator| generator.name)
+            .collect();
+        assert_eq!(wrapped, vec!["binary", "sidewinder", "ellers"]);
+
+        // only kruskals keeps a weave grid's crossings
+        let weave = WeaveGrid::new(4, 4);
+        let weavers: Vec<&str> = GENERATORS
+            .iter()
+            .filter(|generator| generator.supports(&weave))
+            .map(|generator| generator.name)
+            .collect();
+        assert_eq!(weavers, vec!["kruskals"]);
+    }
+
+    #[test]
+    fn every_generator_should_make_a_perfect_maze() {
+        for generator in GENERATORS {
+            let seed = "abc12345abc";
+            let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+            let mut grid: Box<dyn Grid> = Box::new(StandardGrid::new(6, 5));
+            (generator.generate)(&mut grid, &mut rng);
+
+            // every cell reachable, with no loops
+            let link_count: usize = grid.links().values().map(|links| links.len()).sum();
+            assert_eq!(link_count, 2 * 29, "{}", generator.name);
+            let reachable = distances(&grid.map()[0].position, grid.links());
+            assert_eq!(reachable.len(), 30, "{}", generator.name);
+        }
+    }
+
+    #[test]
+    fn every_supported_generator_should_make_a_perfect_maze_when_wrapped() {
+        for topology in [Topology::Cylinder, Topology::Torus, Topology::Klein] {
+            for generator in GENERATORS {
+                let grid = StandardGrid::with_topology(6, 5, topology);
+                if !generator.supports(&grid) {
+                    continue;
+                }
+                let seed = "abc12345abc";
+                let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+                let mut grid: Box<dyn Grid> = Box::new(grid);
+                (generator.generate)(&mut grid, &mut rng);
+
+                let link_count: usize = grid.links().values().map(|links| links.len()).sum();
+                assert_eq!(link_count, 2 * 29, "{} {:?}", generator.name, topology);
+                let reachable = distances(&grid.map()[0].position, grid.links());
+                assert_eq!(reachable.len(), 30, "{} {:?}", generator.name, topology);
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use rand::rngs::SmallRng;
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::Position;
+use crate::algos::link;
+use crate::grid::Grid;
+
+// grow the maze outwards from a random cell: pick any cell on the edge of the
+// maze and link it to a random unvisited neighbour. gives a radial texture
+// with lots of short dead ends
+pub fn simplified_prims(grid: &mut Box<dyn Grid>, rng: &mut SmallRng) {
+    let mut links = grid.links().clone();
+    let Some(start) = grid.map().choose(rng) else {
+        return;
+    };
+
+    let mut active = vec![start.position];
+    let mut visited = HashSet::from([start.position]);
+
+    while !active.is_empty() {
+        let index = rng.random_range(0..active.len());
+        let current = active[index];
+        let cell = grid.cell_at(&current).expect("Couldn't find current cell");
+        let available: Vec<_> = cell
+            .get_neighbours()
+            .into_iter()
+            .filter(|neighbour| !visited.contains(neighbour))
+            .collect();
+
+        match available.choose(rng) {
+            Some(neighbour) => {
+                link(&mut links, &current, neighbour);
+                visited.insert(*neighbour);
+                active.push(*neighbour);
+            }
+            None => {
+                active.remove(index);
+            }
+        }
+    }
+
+    grid.set_links(links);
+}
+
+// as simplified prim's, but every cell gets a random cost up front and the
+// cheapest cells are always grown first
+pub fn true_prims(grid: &mut Box<dyn Grid>, rng: &mut SmallRng) {
+    let mut links = grid.links().clone();
+    let Some(start) = grid.map().choose(rng) else {
+        return;
+    };
+
+    let costs: HashMap<Position, i32> = grid
+        .map()
+        .iter()
+        .map(|cell| (cell.position, rng.random_range(0..100)))
+        .collect();
+
+    // cheapest first. of equal costs, the one added first comes out first,
+    // so ties are repeatable
+    let mut active = BinaryHeap::from([Reverse((
+        costs[&start.position],
+        0,
+        start.position.y,
+        start.position.x,
+    ))]);
+    let mut added = 1;
+    let mut visited = HashSet::from([start.position]);
+
+    while let Some(Reverse((_, _, y, x))) = active.peek().copied() {
+        let current = Position { x, y };
+        let cell = grid.cell_at(&current).expect("Couldn't find current cell");
+        let cheapest = cell
+            .get_neighbours()
+            .into_iter()
+            .filter(|neighbour| !visited.contains(neighbour))
+            .min_by_key(|neighbour| costs[neighbour]);
+
+        match cheapest {
+            Some(neighbour) => {
+                link(&mut links, &current, &neighbour);
+                visited.insert(neighbour);
+                active.push(Reverse((
+                    costs[&neighbour],
+                    added,
+                    neighbour.y,
+                    neighbour.x,
+                )));
+                added += 1;
+            }
+            None => {
+                active.pop();
+            }
+        }
+    }
+
+    grid.set_links(links);
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        algos::prims::{simplified_prims, true_prims},
+        grid::{Grid, StandardGrid},
+    };
+
+    #[test]
+    fn should_generate_simplified_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
+        simplified_prims(&mut container, &mut rng);
+
+        assert_eq!(
+            format!("{}", container),
+            "+---+---+---+---+
+|               |
++   +---+---+---+
+|   |           |
++   +   +---+---+
+|   |       |   |
++   +   +---+   +
+|               |
++---+---+---+---+
+"
+        );
+    }
+
+    #[test]
+    fn should_generate_true_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
+        true_prims(&mut container, &mut rng);
+
+        assert_eq!(
+            format!("{}", container),
+            "+---+---+---+---+
+|   |   |       |
++   +   +---+   +
+|       |       |
++---+   +   +---+
+|   |           |
++   +   +   +   +
+|       |   |   |
++---+---+---+---+
+"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+
+use rand::rngs::SmallRng;
+use rand::seq::IndexedRandom;
+
+use crate::algos::link;
+use crate::grid::Grid;
+
+// depth-first search: walk to a random unvisited neighbour, backing up the
+// path whenever there isn't one. uses an explicit stack rather than recursion
+// so very large grids don't overflow
+pub fn recursive_backtracker(grid: &mut Box<dyn Grid>, rng: &mut SmallRng) {
+    let mut links = grid.links().clone();
+    let Some(start) = grid.map().choose(rng) else {
+        return;
+    };
+
+    let mut stack = vec![start.position];
+    let mut visited = HashSet::from([start.position]);
+
+    while let Some(current) = stack.last().copied() {
+        let cell = grid.cell_at(&current).expect("Couldn't find current cell");
+        let unvisited: Vec<_> = cell
+            .get_neighbours()
+            .into_iter()
+            .filter(|neighbour| !visited.contains(neighbour))
+            .collect();
+
+        match unvisited.choose(rng) {
+            Some(neighbour) => {
+                link(&mut links, &current, neighbour);
+                visited.insert(*neighbour);
+                stack.push(*neighbour);
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+
+    grid.set_links(links);
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        algos::recursive_backtracker::recursive_backtracker,
+        grid::{Grid, StandardGrid},
+    };
+
+    #[test]
+    fn should_generate_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
+        recursive_backtracker(&mut container, &mut rng);
+
+        assert_eq!(
+            format!("{}", container),
+            "+---+---+---+---+
+|   |           |
++   +   +   +---+
+|       |       |
++---+---+---+   +
+|       |       |
++---+   +   +   +
+|           |   |
++---+---+---+---+
+"
+        );
+    }
+
+    #[test]
+    fn should_not_overflow_on_long_passages() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        // a single row forces the search as deep as the grid is wide
+        let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(200_000, 1));
+        recursive_backtracker(&mut container, &mut rng);
+
+        let link_count: usize = container.links().values().map(|links| links.len()).sum();
+        assert_eq!(link_count, 2 * (200_000 - 1));
+    }
+}
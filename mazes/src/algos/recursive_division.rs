@@ -0,0 +1,192 @@
+use rand::Rng;
+use rand::rngs::SmallRng;
+
+use crate::algos::{link, unlink};
+use crate::{Position, grid::Grid};
+
+// an area of the grid still to be divided
+struct Region {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+// start with every cell linked to every neighbour, then keep splitting the
+// grid in two with a wall that has a single gap in it. the only algorithm
+// here that adds walls rather than carving passages. the walls only split
+// the flat grid, so edges joined by a topology are left closed rather than
+// making loops
+pub fn recursive_division(grid: &mut Box<dyn Grid>, rng: &mut SmallRng) {
+    recursive_division_with(grid, rng, None);
+}
+
+// regions no bigger than `room_size` in either direction are sometimes left
+// undivided, giving open rectangular rooms
+pub fn recursive_division_with(
+    grid: &mut Box<dyn Grid>,
+    rng: &mut SmallRng,
+    room_size: Option<i32>,
+) {
+    let mut links = grid.links().clone();
+    for cell in grid.map() {
+        for neighbour in cell.get_neighbours() {
+            let (dx, dy) = (neighbour.x - cell.position.x, neighbour.y - cell.position.y);
+            if dx.abs() + dy.abs() == 1 {
+                link(&mut links, &cell.position, &neighbour);
+            }
+        }
+    }
+
+    // regions waiting to be divided, rather than recursing
+    let mut regions = vec![Region {
+        x: 0,
+        y: 0,
+        width: grid.width(),
+        height: grid.height(),
+    }];
+
+    while let Some(region) = regions.pop() {
+        if region.width <= 1 || region.height <= 1 {
+            continue;
+        }
+
+        let fits_room = room_size
+            .is_some_and(|room_size| region.width <= room_size && region.height <= room_size);
+        if fits_room && rng.random_ratio(1, 4) {
+            continue;
+        }
+
+        if region.height > region.width {
+            // wall along the south side of one row, with a gap somewhere
+            let wall_y = region.y + rng.random_range(0..region.height - 1);
+            let gap_x = region.x + rng.random_range(0..region.width);
+            for x in region.x..region.x + region.width {
+                if x != gap_x {
+                    unlink(
+                        &mut links,
+                        &Position { x, y: wall_y },
+                        &Position { x, y: wall_y + 1 },
+                    );
+                }
+            }
+
+            let north_height = wall_y - region.y + 1;
+            regions.push(Region {
+                height: north_height,
+                ..region
+            });
+            regions.push(Region {
+                y: wall_y + 1,
+                height: region.height - north_height,
+                ..region
+            });
+        } else {
+            // wall along the east side of one column, with a gap somewhere
+            let wall_x = region.x + rng.random_range(0..region.width - 1);
+            let gap_y = region.y + rng.random_range(0..region.height);
+            for y in region.y..region.y + region.height {
+                if y != gap_y {
+                    unlink(
+                        &mut links,
+                        &Position { x: wall_x, y },
+                        &Position { x: wall_x + 1, y },
+                    );
+                }
+            }
+
+            let west_width = wall_x - region.x + 1;
+            regions.push(Region {
+                width: west_width,
+                ..region
+            });
+            regions.push(Region {
+                x: wall_x + 1,
+                width: region.width - west_width,
+                ..region
+            });
+        }
+    }
+
+    grid.set_links(links);
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        Position, Topology,
+        algos::recursive_division::{recursive_division, recursive_division_with},
+        distances::distances,
+        grid::{Grid, StandardGrid},
+    };
+
+    #[test]
+    fn should_generate_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
+        recursive_division(&mut container, &mut rng);
+
+        assert_eq!(
+            format!("{}", container),
+            "+---+---+---+---+
+|       |       |
++---+   +---+   +
+|       |   |   |
++   +---+   +   +
+|               |
++   +   +   +---+
+|   |   |       |
++---+---+---+---+
+"
+        );
+    }
+
+    #[test]
+    fn should_leave_rooms() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(8, 8));
+        recursive_division_with(&mut container, &mut rng, Some(4));
+
+        assert_eq!(
+            format!("{}", container),
+            "+---+---+---+---+---+---+---+---+
+|   |           |   |   |       |
++   +   +   +   +   +   +   +   +
+|           |   |           |   |
++---+   +---+---+---+---+   +---+
+|           |   |               |
++   +---+   +   +   +   +   +   +
+|   |   |       |               |
++   +   +   +   +   +   +   +   +
+|   |       |                   |
++   +---+---+---+   +   +   +   +
+|               |               |
++   +   +   +   +---+   +---+---+
+|               |           |   |
++   +   +   +   +   +   +   +   +
+|               |       |       |
++---+---+---+---+---+---+---+---+
+"
+        );
+    }
+
+    #[test]
+    fn should_not_loop_around_a_torus() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut container: Box<dyn Grid> =
+            Box::new(StandardGrid::with_topology(5, 4, Topology::Torus));
+        recursive_division(&mut container, &mut rng);
+
+        // still a perfect maze: every cell reachable, with no loops
+        let link_count: usize = container.links().values().map(|links| links.len()).sum();
+        assert_eq!(link_count, 2 * 19);
+        let reachable = distances(&Position { x: 0, y: 0 }, container.links());
+        assert_eq!(reachable.len(), 20);
+    }
+}
@@ -1,16 +1,9 @@
-use std::collections::HashMap;
-
+use crate::Direction;
+use crate::algos::link;
 use crate::grid::Grid;
-use crate::{Direction, Position};
 use rand::rngs::SmallRng;
 use rand::{Rng, seq::IndexedMutRandom};
 
-fn link(links: &mut HashMap<Position, Vec<Position>>, start: &Position, neighbour: &Position) {
-    links.entry(*start).or_insert(vec![]).push(*neighbour);
-    // bi-directional
-    links.entry(*neighbour).or_insert(vec![]).push(*start);
-}
-
 pub fn sidewinder(grid: &mut Box<dyn Grid>, rng: &mut SmallRng) {
     // can't borrow links field inside for loop since Rust doesn't know which field
     // we're mutating
@@ -0,0 +1,111 @@
+use std::collections::{HashMap, HashSet};
+
+use rand::rngs::SmallRng;
+use rand::seq::IndexedRandom;
+
+use crate::Position;
+use crate::algos::{link, reachable};
+use crate::grid::Grid;
+
+// cells still to be walked from. picked at random and taken out one at a
+// time, so each one's index is kept to swap it out without a search
+struct Unvisited {
+    cells: Vec<Position>,
+    indexes: HashMap<Position, usize>,
+}
+
+impl Unvisited {
+    fn new(cells: Vec<Position>) -> Self {
+        let indexes = cells.iter().enumerate().map(|(i, pos)| (*pos, i)).collect();
+        Unvisited { cells, indexes }
+    }
+
+    fn remove(&mut self, pos: &Position) {
+        if let Some(index) = self.indexes.remove(pos) {
+            self.cells.swap_remove(index);
+            if let Some(moved) = self.cells.get(index) {
+                self.indexes.insert(*moved, index);
+            }
+        }
+    }
+}
+
+// loop-erased random walks: wander from an unvisited cell until the walk hits
+// the maze, erasing any loops along the way, then carve the walk into the
+// maze. unbiased like aldous-broder, but slow to start and fast to finish
+pub fn wilsons(grid: &mut Box<dyn Grid>, rng: &mut SmallRng) {
+    let mut links = grid.links().clone();
+    let mut cells: Vec<_> = grid.map().iter().map(|cell| cell.position).collect();
+    let Some(first) = cells.choose(rng).copied() else {
+        return;
+    };
+
+    // walks from cells cut off from the first one would never end
+    let connected = reachable(grid.as_ref(), &first);
+    let mut in_maze = HashSet::from([first]);
+    cells.retain(|pos| *pos != first && connected.contains(pos));
+    let mut unvisited = Unvisited::new(cells);
+
+    while let Some(start) = unvisited.cells.choose(rng).copied() {
+        let mut path = vec![start];
+        let mut current = start;
+
+        while !in_maze.contains(&current) {
+            let cell = grid.cell_at(&current).expect("Couldn't find current cell");
+            let Some(neighbour) = cell.get_neighbours().choose(rng).copied() else {
+                // nowhere to walk to
+                break;
+            };
+
+            // walked back onto the path, so erase the loop
+            match path.iter().position(|pos| *pos == neighbour) {
+                Some(index) => path.truncate(index + 1),
+                None => path.push(neighbour),
+            }
+            current = neighbour;
+        }
+
+        for pair in path.windows(2) {
+            link(&mut links, &pair[0], &pair[1]);
+        }
+        for pos in path {
+            unvisited.remove(&pos);
+            in_maze.insert(pos);
+        }
+    }
+
+    grid.set_links(links);
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        algos::wilsons::wilsons,
+        grid::{Grid, StandardGrid},
+    };
+
+    #[test]
+    fn should_generate_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut container: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
+        wilsons(&mut container, &mut rng);
+
+        assert_eq!(
+            format!("{}", container),
+            "+---+---+---+---+
+|           |   |
++---+---+   +   +
+|               |
++---+   +---+   +
+|   |   |       |
++   +   +---+   +
+|           |   |
++---+---+---+---+
+"
+        );
+    }
+}
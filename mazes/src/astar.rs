@@ -0,0 +1,302 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+};
+
+use crate::{
+    Position, Position3D, Shape, Topology,
+    distances::{Distances, PathError},
+    grid::Grid,
+    weights::Weights,
+};
+
+// guesses how many steps a cell is from the goal. a guess that's never too
+// high still finds the shortest path, it just looks at fewer cells on the
+// way. Zero makes this plain Dijkstra
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Heuristic {
+    Manhattan,
+    Euclidean,
+    Zero,
+}
+
+impl Heuristic {
+    // euclidean is rounded down so it never guesses too high
+    pub fn estimate(&self, from: &Position, to: &Position) -> i32 {
+        let dx = (from.x - to.x).abs();
+        let dy = (from.y - to.y).abs();
+        match self {
+            Heuristic::Manhattan => dx + dy,
+            Heuristic::Euclidean => f64::from(dx).hypot(f64::from(dy)).floor() as i32,
+            Heuristic::Zero => 0,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Solution {
+    // start to goal, both included
+    pub path: Vec<Position>,
+    // sum of the weights of each cell moved into
+    pub cost: i32,
+    // how many cells had their links followed before the goal came up
+    pub expanded: usize,
+}
+
+// the heuristic's guess, kept low enough that it never costs more than the
+// real path. steps are scaled by the lightest cell, levels count stairs as
+// one step however many rows apart the floors are stored, and on any other
+// shape (or wrapped edges) a step isn't one apart in x or y, so it's 0
+fn estimate(
+    grid: &dyn Grid,
+    weights: &Weights,
+    heuristic: Heuristic,
+    from: &Position,
+    to: &Position,
+) -> i32 {
+    if grid.topology() != Topology::Plane {
+        return 0;
+    }
+    let steps = match grid.shape() {
+        Shape::Square => heuristic.estimate(from, to),
+        Shape::Levels => {
+            let from = Position3D::from_position(from, grid.height());
+            let to = Position3D::from_position(to, grid.height());
+            let on_level = heuristic.estimate(
+                &Position {
+                    x: from.x,
+                    y: from.y,
+                },
+                &Position { x: to.x, y: to.y },
+            );
+            match heuristic {
+                Heuristic::Zero => 0,
+                _ => on_level + (from.level - to.level).abs(),
+            }
+        }
+        _ => 0,
+    };
+    steps.saturating_mul(weights.lightest())
+}
+
+// shortest path from start to goal, searching towards the goal first. each
+// step costs the weight of the cell it moves into
+pub fn astar(
+    grid: &dyn Grid,
+    start: &Position,
+    goal: &Position,
+    weights: &Weights,
+    heuristic: Heuristic,
+) -> Result<Solution, PathError> {
+    for pos in [start, goal] {
+        if grid.cell_at(pos).is_none() {
+            return Err(PathError::NotInGrid(*pos));
+        }
+    }
+
+    let mut costs = Distances::new(*start);
+    let mut closed = HashSet::new();
+    let mut expanded = 0;
+    // cheapest guess first. ties go to whichever is nearer the goal, then
+    // row order, so the search is repeatable
+    let guess = |pos: &Position| estimate(grid, weights, heuristic, pos, goal);
+    let estimate = guess(start);
+    let mut open = BinaryHeap::from([Reverse((estimate, estimate, start.y, start.x))]);
+
+    while let Some(Reverse((_, _, y, x))) = open.pop() {
+        let pos = Position { x, y };
+        if pos == *goal {
+            let path = costs.path_to(goal).expect("goal was reached");
+            let cost = costs.get(goal).unwrap_or(0);
+            return Ok(Solution {
+                path,
+                cost,
+                expanded,
+            });
+        }
+        // already expanded through a cheaper route
+        if !closed.insert(pos) {
+            continue;
+        }
+        expanded += 1;
+
+        let cost = costs.get(&pos).unwrap_or(0);
+        for link in grid.links().get(&pos).into_iter().flatten() {
+            let next_cost = cost.saturating_add(weights.get(link));
+            if costs.get(link).is_none_or(|best| next_cost < best) {
+                costs.insert(*link, next_cost, pos);
+                let guess = guess(link);
+                open.push(Reverse((
+                    next_cost.saturating_add(guess),
+                    guess,
+                    link.y,
+                    link.x,
+                )));
+            }
+        }
+    }
+
+    Err(PathError::Unreachable(*start, *goal))
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        Position, Position3D, Topology,
+        algos::recursive_backtracker::recursive_backtracker,
+        distances::{PathError, solve, solve_weighted},
+        grid::{Grid, StandardGrid},
+        grid_3d::Grid3D,
+        weights::Weights,
+    };
+
+    use super::{Heuristic, astar, estimate};
+
+    #[test]
+    fn it_should_estimate_distances() {
+        let from = Position { x: 0, y: 0 };
+        let to = Position { x: 3, y: 4 };
+        assert_eq!(Heuristic::Manhattan.estimate(&from, &to), 7);
+        assert_eq!(Heuristic::Euclidean.estimate(&from, &to), 5);
+        assert_eq!(Heuristic::Zero.estimate(&from, &to), 0);
+    }
+
+    #[test]
+    fn it_should_find_the_same_path_as_bfs() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut grid: Box<dyn Grid> = Box::new(StandardGrid::new(20, 20));
+        recursive_backtracker(&mut grid, &mut rng);
+
+        let start = Position { x: 0, y: 0 };
+        let goal = Position { x: 19, y: 19 };
+        // a perfect maze only has one path between two cells
+        let expected = solve(grid.as_ref(), &start, &goal).unwrap();
+        let weights = Weights::new();
+
+        let zero = astar(grid.as_ref(), &start, &goal, &weights, Heuristic::Zero).unwrap();
+        let manhattan =
+            astar(grid.as_ref(), &start, &goal, &weights, Heuristic::Manhattan).unwrap();
+        let euclidean =
+            astar(grid.as_ref(), &start, &goal, &weights, Heuristic::Euclidean).unwrap();
+        for solution in [&zero, &manhattan, &euclidean] {
+            assert_eq!(solution.path, expected);
+            assert_eq!(solution.cost, expected.len() as i32 - 1);
+        }
+        assert_eq!(zero.expanded, 391);
+        assert_eq!(manhattan.expanded, 375);
+        assert!(euclidean.expanded <= zero.expanded);
+    }
+
+    #[test]
+    fn it_should_expand_fewer_cells_with_a_heuristic() {
+        // no walls at all, so every cell is a candidate
+        let grid = open_grid(Box::new(StandardGrid::new(20, 20)));
+
+        let start = Position { x: 0, y: 0 };
+        let goal = Position { x: 19, y: 19 };
+        let weights = Weights::new();
+        let zero = astar(grid.as_ref(), &start, &goal, &weights, Heuristic::Zero).unwrap();
+        let manhattan =
+            astar(grid.as_ref(), &start, &goal, &weights, Heuristic::Manhattan).unwrap();
+        assert_eq!(zero.cost, 38);
+        assert_eq!(manhattan.cost, 38);
+        assert_eq!(zero.expanded, 399);
+        assert_eq!(manhattan.expanded, 38);
+    }
+
+    #[test]
+    fn it_should_report_unreachable_goals() {
+        let grid: Box<dyn Grid> = Box::new(StandardGrid::new(3, 3));
+        let start = Position { x: 0, y: 0 };
+        let goal = Position { x: 2, y: 2 };
+        let weights = Weights::new();
+
+        assert_eq!(
+            astar(grid.as_ref(), &start, &goal, &weights, Heuristic::Manhattan),
+            Err(PathError::Unreachable(start, goal))
+        );
+        let outside = Position { x: -1, y: 0 };
+        assert_eq!(
+            astar(grid.as_ref(), &outside, &goal, &weights, Heuristic::Zero),
+            Err(PathError::NotInGrid(outside))
+        );
+        let solution = astar(grid.as_ref(), &start, &start, &weights, Heuristic::Zero).unwrap();
+        assert_eq!((solution.path, solution.cost), (vec![start], 0));
+    }
+
+    // every cell linked to every neighbour
+    fn open_grid(mut grid: Box<dyn Grid>) -> Box<dyn Grid> {
+        let cells = grid.map().clone();
+        for cell in cells {
+            for neighbour in cell.get_neighbours() {
+                grid.link(&cell.position, &neighbour);
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn it_should_take_a_zero_weight_shortcut() {
+        let grid = open_grid(Box::new(StandardGrid::new(5, 3)));
+        let start = Position { x: 0, y: 0 };
+        let goal = Position { x: 4, y: 0 };
+
+        // straight along the top costs 4, round the free bottom row costs 1
+        let mut weights = Weights::new();
+        for x in 0..5 {
+            weights.set(&Position { x, y: 2 }, 0);
+        }
+        weights.set(&Position { x: 0, y: 1 }, 0);
+        weights.set(&Position { x: 4, y: 1 }, 0);
+
+        let expected = solve_weighted(grid.as_ref(), &start, &goal, &weights).unwrap();
+        for heuristic in [Heuristic::Manhattan, Heuristic::Euclidean] {
+            let solution = astar(grid.as_ref(), &start, &goal, &weights, heuristic).unwrap();
+            assert_eq!(solution.cost, 1);
+            assert_eq!(solution.path, expected);
+        }
+    }
+
+    #[test]
+    fn it_should_never_guess_too_high() {
+        let weights = Weights::new();
+
+        // stairs are one step, though the floors are stored height rows apart
+        let grid = open_grid(Box::new(Grid3D::new(3, 4, 2)));
+        let below = Position3D {
+            x: 0,
+            y: 0,
+            level: 1,
+        }
+        .to_position(4);
+        let origin = Position { x: 0, y: 0 };
+        let guess = estimate(
+            grid.as_ref(),
+            &weights,
+            Heuristic::Manhattan,
+            &origin,
+            &below,
+        );
+        assert_eq!(guess, 1);
+        let solution = astar(
+            grid.as_ref(),
+            &origin,
+            &below,
+            &weights,
+            Heuristic::Manhattan,
+        );
+        assert_eq!(solution.unwrap().cost, 1);
+
+        // the far corner of a torus is one step away
+        let torus = StandardGrid::with_topology(4, 4, Topology::Torus);
+        let corner = Position { x: 3, y: 3 };
+        assert_eq!(
+            estimate(&torus, &weights, Heuristic::Manhattan, &origin, &corner),
+            0
+        );
+    }
+}
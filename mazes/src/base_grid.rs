@@ -1,9 +1,30 @@
-use crate::{Direction, Position, cell::Cell, grid::Grid};
+use std::{
+    collections::{HashMap, HashSet},
+    f64::consts::PI,
+};
+
+use crate::{Direction, Position, Shape, Topology, cell::Cell, grid::Grid};
+
+pub(crate) const CELL_SIZE: i32 = 16;
+pub(crate) const WALL_COLOUR: &str = "black";
+// how far passages across joined edges stick out
+const STUB_LENGTH: i32 = CELL_SIZE / 4;
 
 pub trait Svg: Grid {
-    fn draw(&self, map: &Vec<Cell>, width: i32, height: i32) -> String {
-        let wall_colour = "black";
-        let cell_size = 16;
+    fn draw(&self, map: &[Cell], width: i32, height: i32) -> String {
+        match self.shape() {
+            Shape::Square => {}
+            Shape::Polar => return self.draw_polar(map, height),
+            Shape::Hex => return self.draw_hex(map, width, height),
+            Shape::Triangle => return self.draw_triangle(map, width, height),
+            Shape::Weave => return self.draw_weave(map, width, height),
+            Shape::Levels => return self.draw_levels(map, width, height),
+            Shape::Upsilon => return self.draw_upsilon(map, width, height),
+            Shape::Graph => return self.draw_graph(map, width, height),
+        }
+
+        let wall_colour = WALL_COLOUR;
+        let cell_size = CELL_SIZE;
         let mut output = String::new();
 
         map.iter().for_each(|cell| {
@@ -12,47 +33,530 @@ pub trait Svg: Grid {
             let x2 = (cell.position.x + 1) * cell_size;
             let y2 = (cell.position.y + 1) * cell_size;
 
+            // a neighbour that isn't the next cell along is across a joined edge
+            let wrapped = |direction: Direction, pos: &Position| {
+                let (dx, dy) = match direction {
+                    Direction::North => (0, -1),
+                    Direction::South => (0, 1),
+                    Direction::West => (-1, 0),
+                    _ => (1, 0),
+                };
+                *pos != Position {
+                    x: cell.position.x + dx,
+                    y: cell.position.y + dy,
+                }
+            };
+            // walls on a joined edge belong to both cells either side, so each
+            // draws its own. a passage through one is shown by short stubs
+            // sticking out past the edge
+            let edge = |pos: &Position, side: (i32, i32, i32, i32), out: (i32, i32)| {
+                let (ax, ay, bx, by) = side;
+                if self.is_linked(&cell.position, pos) {
+                    let (dx, dy) = (out.0 * STUB_LENGTH, out.1 * STUB_LENGTH);
+                    Self::svg_line(ax, ay, ax + dx, ay + dy, wall_colour)
+                        + &Self::svg_line(bx, by, bx + dx, by + dy, wall_colour)
+                } else {
+                    Self::svg_line(ax, ay, bx, by, wall_colour)
+                }
+            };
+
             // draw north and west if there are no cells in that direction
             // (ie outside)
-            let north = cell.neighbours.get(&Direction::North);
-            if north.is_none() {
-                output += &Self::svg_line(x1, y1, x2, y1, wall_colour);
+            match cell.neighbours.get(&Direction::North) {
+                None => output += &Self::svg_line(x1, y1, x2, y1, wall_colour),
+                Some(north) if wrapped(Direction::North, north) => {
+                    output += &edge(north, (x1, y1, x2, y1), (0, -1))
+                }
+                _ => {}
             }
 
-            let west = cell.neighbours.get(&Direction::West);
-            if west.is_none() {
-                output += &Self::svg_line(x1, y1, x1, y2, wall_colour);
+            match cell.neighbours.get(&Direction::West) {
+                None => output += &Self::svg_line(x1, y1, x1, y2, wall_colour),
+                Some(west) if wrapped(Direction::West, west) => {
+                    output += &edge(west, (x1, y1, x1, y2), (-1, 0))
+                }
+                _ => {}
             }
 
             // draw east and south if there is no cell (outside)
             // or if there is no link to that direction
-            let east = cell.neighbours.get(&Direction::East);
-            if east.is_none() || (east.is_some() && !self.is_linked(&cell.position, east.unwrap()))
-            {
-                output += &Self::svg_line(x2, y1, x2, y2, wall_colour);
+            match cell.neighbours.get(&Direction::East) {
+                Some(east) if wrapped(Direction::East, east) => {
+                    output += &edge(east, (x2, y1, x2, y2), (1, 0))
+                }
+                Some(east) if self.is_linked(&cell.position, east) => {}
+                _ => output += &Self::svg_line(x2, y1, x2, y2, wall_colour),
             }
 
-            let south = cell.neighbours.get(&Direction::South);
-            if south.is_none()
-                || (south.is_some() && !self.is_linked(&cell.position, south.unwrap()))
-            {
-                output += &Self::svg_line(x1, y2, x2, y2, wall_colour);
+            match cell.neighbours.get(&Direction::South) {
+                Some(south) if wrapped(Direction::South, south) => {
+                    output += &edge(south, (x1, y2, x2, y2), (0, 1))
+                }
+                Some(south) if self.is_linked(&cell.position, south) => {}
+                _ => output += &Self::svg_line(x1, y2, x2, y2, wall_colour),
             }
         });
+
+        // room round the outside for the stubs
+        let margin = match self.topology() {
+            Topology::Plane => 0,
+            _ => STUB_LENGTH,
+        };
         let svg = format!(
-            "<svg viewBox=\"0 0 {} {}\" xmlns=\"http://www.w3.org/2000/svg\">{}</svg>",
-            cell_size * width,
-            cell_size * height,
+            "<svg viewBox=\"{} {} {} {}\" xmlns=\"http://www.w3.org/2000/svg\">{}</svg>",
+            -margin,
+            -margin,
+            cell_size * width + 2 * margin,
+            cell_size * height + 2 * margin,
             output
         );
         svg
     }
 
-    fn svg_line(x1: i32, y1: i32, x2: i32, y2: i32, wall_colour: &str) -> String {
+    // each ring is a cell deep, with the centre cell in the middle
+    fn draw_polar(&self, map: &[Cell], rings: i32) -> String {
+        let cell_size = CELL_SIZE as f64;
+        let size = 2.0 * rings as f64 * cell_size;
+        let centre = size / 2.0;
+        let mut output = String::new();
+
+        let mut counts: HashMap<i32, i32> = HashMap::new();
+        for cell in map {
+            *counts.entry(cell.position.y).or_insert(0) += 1;
+        }
+
+        for cell in map {
+            let ring = cell.position.y;
+            if ring == 0 {
+                continue;
+            }
+
+            let theta = 2.0 * PI / counts[&ring] as f64;
+            let inner_radius = ring as f64 * cell_size;
+            let outer_radius = (ring + 1) as f64 * cell_size;
+            let theta_ccw = cell.position.x as f64 * theta;
+            let theta_cw = (cell.position.x + 1) as f64 * theta;
+
+            let ax = centre + inner_radius * theta_ccw.cos();
+            let ay = centre + inner_radius * theta_ccw.sin();
+            let cx = centre + inner_radius * theta_cw.cos();
+            let cy = centre + inner_radius * theta_cw.sin();
+            let dx = centre + outer_radius * theta_cw.cos();
+            let dy = centre + outer_radius * theta_cw.sin();
+
+            // inner wall as an arc, and the clockwise wall as a line. the
+            // outer and counter-clockwise walls belong to the neighbours
+            let inward = cell.neighbours.get(&Direction::Inward);
+            if inward.is_none_or(|pos| !self.is_linked(&cell.position, pos)) {
+                output += &svg_arc(ax, ay, cx, cy, inner_radius, WALL_COLOUR);
+            }
+
+            let clockwise = cell.neighbours.get(&Direction::Clockwise);
+            if clockwise.is_none_or(|pos| !self.is_linked(&cell.position, pos)) {
+                output += &svg_line_f64(cx, cy, dx, dy, WALL_COLOUR);
+            }
+        }
+
+        output += &format!(
+            "<circle cx=\"{centre:.2}\" cy=\"{centre:.2}\" r=\"{:.2}\" stroke=\"{WALL_COLOUR}\" fill=\"none\" />",
+            rings as f64 * cell_size
+        );
+
+        format!(
+            "<svg viewBox=\"0 0 {size} {size}\" xmlns=\"http://www.w3.org/2000/svg\">{}</svg>",
+            output
+        )
+    }
+
+    // hexagons with sides cell_size long
+    fn draw_hex(&self, map: &[Cell], width: i32, height: i32) -> String {
+        let size = CELL_SIZE as f64;
+        let a_size = size / 2.0;
+        let b_size = size * 3.0_f64.sqrt() / 2.0;
+        let cell_height = b_size * 2.0;
+        let mut output = String::new();
+
+        for cell in map {
+            let col = cell.position.x;
+            let row = cell.position.y;
+            let cx = size + 3.0 * col as f64 * a_size;
+            let mut cy = b_size + row as f64 * cell_height;
+            if col % 2 == 1 {
+                cy += b_size;
+            }
+
+            // far west, near west, near east, far east
+            let x_fw = cx - size;
+            let x_nw = cx - a_size;
+            let x_ne = cx + a_size;
+            let x_fe = cx + size;
+            // north, middle, south
+            let y_n = cy - b_size;
+            let y_m = cy;
+            let y_s = cy + b_size;
+
+            // walls on the west and north sides only if there's no cell
+            // there, the others if there's no cell or no link
+            let neighbour = |direction| cell.neighbours.get(&direction);
+            let unlinked = |direction| {
+                neighbour(direction).is_none_or(|pos| !self.is_linked(&cell.position, pos))
+            };
+
+            if neighbour(Direction::SouthWest).is_none() {
+                output += &svg_line_f64(x_fw, y_m, x_nw, y_s, WALL_COLOUR);
+            }
+            if neighbour(Direction::NorthWest).is_none() {
+                output += &svg_line_f64(x_fw, y_m, x_nw, y_n, WALL_COLOUR);
+            }
+            if neighbour(Direction::North).is_none() {
+                output += &svg_line_f64(x_nw, y_n, x_ne, y_n, WALL_COLOUR);
+            }
+            if unlinked(Direction::NorthEast) {
+                output += &svg_line_f64(x_ne, y_n, x_fe, y_m, WALL_COLOUR);
+            }
+            if unlinked(Direction::SouthEast) {
+                output += &svg_line_f64(x_fe, y_m, x_ne, y_s, WALL_COLOUR);
+            }
+            if unlinked(Direction::South) {
+                output += &svg_line_f64(x_ne, y_s, x_nw, y_s, WALL_COLOUR);
+            }
+        }
+
+        let image_width = 3.0 * a_size * width as f64 + a_size;
+        let image_height = cell_height * height as f64 + b_size;
+        format!(
+            "<svg viewBox=\"0 0 {image_width:.2} {image_height:.2}\" xmlns=\"http://www.w3.org/2000/svg\">{}</svg>",
+            output
+        )
+    }
+
+    fn draw_triangle(&self, map: &[Cell], width: i32, height: i32) -> String {
+        let size = CELL_SIZE as f64;
+        let half_width = size / 2.0;
+        let cell_height = size * 3.0_f64.sqrt() / 2.0;
+        let half_height = cell_height / 2.0;
+        let mut output = String::new();
+
+        for cell in map {
+            let col = cell.position.x;
+            let row = cell.position.y;
+            let upright = (col + row) % 2 == 0;
+            let cx = half_width + col as f64 * half_width;
+            let cy = half_height + row as f64 * cell_height;
+
+            let x_w = cx - half_width;
+            let x_m = cx;
+            let x_e = cx + half_width;
+            let (y_apex, y_base) = if upright {
+                (cy - half_height, cy + half_height)
+            } else {
+                (cy + half_height, cy - half_height)
+            };
+
+            let neighbour = |direction| cell.neighbours.get(&direction);
+            let unlinked = |direction| {
+                neighbour(direction).is_none_or(|pos| !self.is_linked(&cell.position, pos))
+            };
+
+            if neighbour(Direction::West).is_none() {
+                output += &svg_line_f64(x_w, y_base, x_m, y_apex, WALL_COLOUR);
+            }
+            if unlinked(Direction::East) {
+                output += &svg_line_f64(x_e, y_base, x_m, y_apex, WALL_COLOUR);
+            }
+            // the flat side is shared with the cell above or below. upright
+            // cells only draw it on the edge, inverted cells own it otherwise
+            if (upright && neighbour(Direction::South).is_none())
+                || (!upright && unlinked(Direction::North))
+            {
+                output += &svg_line_f64(x_e, y_base, x_w, y_base, WALL_COLOUR);
+            }
+        }
+
+        let image_width = size * (width + 1) as f64 / 2.0;
+        let image_height = cell_height * height as f64;
         format!(
-            "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" stroke=\"{wall_colour}\" stroke-linecap=\"square\" />"
+            "<svg viewBox=\"0 0 {image_width:.2} {image_height:.2}\" xmlns=\"http://www.w3.org/2000/svg\">{}</svg>",
+            output
         )
     }
+
+    // passages are drawn narrower than the cells so crossings can show one
+    // going under the other. each cell draws its own inner box, with a wall
+    // across each side it isn't linked through and a corridor out to the edge
+    // on the sides it is
+    fn draw_weave(&self, map: &[Cell], width: i32, height: i32) -> String {
+        let size = CELL_SIZE as f64;
+        let inset = size * 0.25;
+        let mut output = String::new();
+
+        for cell in map {
+            let pos = cell.position;
+            // outer edge, inner box, inner box, outer edge
+            let x1 = pos.x as f64 * size;
+            let x2 = x1 + inset;
+            let x4 = x1 + size;
+            let x3 = x4 - inset;
+            let y1 = pos.y as f64 * size;
+            let y2 = y1 + inset;
+            let y4 = y1 + size;
+            let y3 = y4 - inset;
+
+            let linked = |direction| {
+                cell.neighbours
+                    .get(&direction)
+                    .is_some_and(|neighbour| self.is_linked(&pos, neighbour))
+            };
+
+            if linked(Direction::North) {
+                output += &svg_line_f64(x2, y1, x2, y2, WALL_COLOUR);
+                output += &svg_line_f64(x3, y1, x3, y2, WALL_COLOUR);
+            } else {
+                output += &svg_line_f64(x2, y2, x3, y2, WALL_COLOUR);
+            }
+            if linked(Direction::South) {
+                output += &svg_line_f64(x2, y3, x2, y4, WALL_COLOUR);
+                output += &svg_line_f64(x3, y3, x3, y4, WALL_COLOUR);
+            } else {
+                output += &svg_line_f64(x2, y3, x3, y3, WALL_COLOUR);
+            }
+            if linked(Direction::West) {
+                output += &svg_line_f64(x1, y2, x2, y2, WALL_COLOUR);
+                output += &svg_line_f64(x1, y3, x2, y3, WALL_COLOUR);
+            } else {
+                output += &svg_line_f64(x2, y2, x2, y3, WALL_COLOUR);
+            }
+            if linked(Direction::East) {
+                output += &svg_line_f64(x3, y2, x4, y2, WALL_COLOUR);
+                output += &svg_line_f64(x3, y3, x4, y3, WALL_COLOUR);
+            } else {
+                output += &svg_line_f64(x3, y2, x3, y3, WALL_COLOUR);
+            }
+
+            // a tunnel underneath shows as the corridor stopping short
+            // either side of the passage on top
+            let west = Position {
+                x: pos.x - 1,
+                y: pos.y,
+            };
+            let east = Position {
+                x: pos.x + 1,
+                y: pos.y,
+            };
+            if self.is_linked(&west, &east) {
+                output += &svg_line_f64(x1, y2, x2, y2, WALL_COLOUR);
+                output += &svg_line_f64(x1, y3, x2, y3, WALL_COLOUR);
+                output += &svg_line_f64(x3, y2, x4, y2, WALL_COLOUR);
+                output += &svg_line_f64(x3, y3, x4, y3, WALL_COLOUR);
+            }
+            let north = Position {
+                x: pos.x,
+                y: pos.y - 1,
+            };
+            let south = Position {
+                x: pos.x,
+                y: pos.y + 1,
+            };
+            if self.is_linked(&north, &south) {
+                output += &svg_line_f64(x2, y1, x2, y2, WALL_COLOUR);
+                output += &svg_line_f64(x3, y1, x3, y2, WALL_COLOUR);
+                output += &svg_line_f64(x2, y3, x2, y4, WALL_COLOUR);
+                output += &svg_line_f64(x3, y3, x3, y4, WALL_COLOUR);
+            }
+        }
+
+        format!(
+            "<svg viewBox=\"0 0 {} {}\" xmlns=\"http://www.w3.org/2000/svg\">{}</svg>",
+            CELL_SIZE * width,
+            CELL_SIZE * height,
+            output
+        )
+    }
+
+    // each level as its own panel, left to right with a cell's gap between
+    // them. stairs are marked with an arrow pointing up or down
+    fn draw_levels(&self, map: &[Cell], width: i32, height: i32) -> String {
+        let cell_size = CELL_SIZE;
+        let panel_width = width * cell_size + cell_size;
+        let mut output = String::new();
+
+        for cell in map {
+            let level = cell.position.y / height;
+            let x1 = level * panel_width + cell.position.x * cell_size;
+            let y1 = (cell.position.y % height) * cell_size;
+            let x2 = x1 + cell_size;
+            let y2 = y1 + cell_size;
+            let mid = x1 + cell_size / 2;
+
+            let neighbour = |direction| cell.neighbours.get(&direction);
+            let linked = |direction| {
+                neighbour(direction).is_some_and(|pos| self.is_linked(&cell.position, pos))
+            };
+
+            if neighbour(Direction::North).is_none() {
+                output += &svg_line(x1, y1, x2, y1, WALL_COLOUR);
+            }
+            if neighbour(Direction::West).is_none() {
+                output += &svg_line(x1, y1, x1, y2, WALL_COLOUR);
+            }
+            if !linked(Direction::East) {
+                output += &svg_line(x2, y1, x2, y2, WALL_COLOUR);
+            }
+            if !linked(Direction::South) {
+                output += &svg_line(x1, y2, x2, y2, WALL_COLOUR);
+            }
+
+            if linked(Direction::Up) {
+                output += &svg_line(mid - 3, y1 + 7, mid, y1 + 4, WALL_COLOUR);
+                output += &svg_line(mid, y1 + 4, mid + 3, y1 + 7, WALL_COLOUR);
+            }
+            if linked(Direction::Down) {
+                output += &svg_line(mid - 3, y2 - 7, mid, y2 - 4, WALL_COLOUR);
+                output += &svg_line(mid, y2 - 4, mid + 3, y2 - 7, WALL_COLOUR);
+            }
+        }
+
+        format!(
+            "<svg viewBox=\"0 0 {} {}\" xmlns=\"http://www.w3.org/2000/svg\">{}</svg>",
+            panel_width * self.levels() - cell_size,
+            cell_size * height,
+            output
+        )
+    }
+
+    // cell centres are a cell apart. octagons are wide enough to touch the
+    // octagons diagonally next to them, and squares fit the gap left between
+    // four octagons. each cell draws the walls on its edge, and the walls it
+    // shares on its east and south sides
+    fn draw_upsilon(&self, map: &[Cell], width: i32, height: i32) -> String {
+        let size = CELL_SIZE as f64;
+        // half the octagon's width, and half the length of its straight sides
+        let half_octagon = size * 2.0_f64.sqrt() / 2.0;
+        let half_side = size - half_octagon;
+        let mut output = String::new();
+
+        for cell in map {
+            let cx = half_octagon + cell.position.x as f64 * size;
+            let cy = half_octagon + cell.position.y as f64 * size;
+            let (h, q) = (half_octagon, half_side);
+
+            // corners of each side relative to the centre, going clockwise
+            let sides = if (cell.position.x + cell.position.y) % 2 == 0 {
+                vec![
+                    (Direction::North, (-q, -h), (q, -h)),
+                    (Direction::NorthEast, (q, -h), (h, -q)),
+                    (Direction::East, (h, -q), (h, q)),
+                    (Direction::SouthEast, (h, q), (q, h)),
+                    (Direction::South, (q, h), (-q, h)),
+                    (Direction::SouthWest, (-q, h), (-h, q)),
+                    (Direction::West, (-h, q), (-h, -q)),
+                    (Direction::NorthWest, (-h, -q), (-q, -h)),
+                ]
+            } else {
+                vec![
+                    (Direction::North, (-q, -q), (q, -q)),
+                    (Direction::East, (q, -q), (q, q)),
+                    (Direction::South, (q, q), (-q, q)),
+                    (Direction::West, (-q, q), (-q, -q)),
+                ]
+            };
+
+            for (direction, start, end) in sides {
+                let owns_shared = matches!(
+                    direction,
+                    Direction::East
+                        | Direction::South
+                        | Direction::SouthEast
+                        | Direction::SouthWest
+                );
+                let wall = match cell.neighbours.get(&direction) {
+                    None => true,
+                    Some(pos) => owns_shared && !self.is_linked(&cell.position, pos),
+                };
+                if wall {
+                    output += &svg_line_f64(
+                        cx + start.0,
+                        cy + start.1,
+                        cx + end.0,
+                        cy + end.1,
+                        WALL_COLOUR,
+                    );
+                }
+            }
+        }
+
+        let image_width = (width - 1) as f64 * size + 2.0 * half_octagon;
+        let image_height = (height - 1) as f64 * size + 2.0 * half_octagon;
+        format!(
+            "<svg viewBox=\"0 0 {image_width:.2} {image_height:.2}\" xmlns=\"http://www.w3.org/2000/svg\">{}</svg>",
+            output
+        )
+    }
+
+    // draws every cell's polygon. a side facing another cell is left open if
+    // the two are linked. otherwise, when both cells have the side, only the
+    // one first in row order draws it
+    fn draw_graph(&self, map: &[Cell], width: i32, height: i32) -> String {
+        let size = CELL_SIZE as f64;
+        let faces = |from: &Position, to: &Position| {
+            self.polygon_sides(from)
+                .is_some_and(|sides| sides.contains(&Some(*to)))
+        };
+
+        let mut output = String::new();
+        for cell in map {
+            let Some(polygon) = self.polygon(&cell.position) else {
+                continue;
+            };
+            let sides = self.polygon_sides(&cell.position).unwrap_or(&[]);
+
+            for (i, a) in polygon.iter().enumerate() {
+                let b = polygon[(i + 1) % polygon.len()];
+                let wall = match sides.get(i).copied().flatten() {
+                    None => true,
+                    Some(other) if self.is_linked(&cell.position, &other) => false,
+                    Some(other) => {
+                        let first = (cell.position.y, cell.position.x) < (other.y, other.x);
+                        first || !faces(&other, &cell.position)
+                    }
+                };
+                if wall {
+                    output +=
+                        &svg_line_f64(a.0 * size, a.1 * size, b.0 * size, b.1 * size, WALL_COLOUR);
+                }
+            }
+        }
+
+        format!(
+            "<svg viewBox=\"0 0 {} {}\" xmlns=\"http://www.w3.org/2000/svg\">{}</svg>",
+            CELL_SIZE * width,
+            CELL_SIZE * height,
+            output
+        )
+    }
+
+    fn svg_line(x1: i32, y1: i32, x2: i32, y2: i32, wall_colour: &str) -> String {
+        svg_line(x1, y1, x2, y2, wall_colour)
+    }
+}
+
+fn svg_line_f64(x1: f64, y1: f64, x2: f64, y2: f64, wall_colour: &str) -> String {
+    format!(
+        "<line x1=\"{x1:.2}\" y1=\"{y1:.2}\" x2=\"{x2:.2}\" y2=\"{y2:.2}\" stroke=\"{wall_colour}\" stroke-linecap=\"square\" />"
+    )
+}
+
+// clockwise arc around the centre, from (x1, y1) to (x2, y2)
+fn svg_arc(x1: f64, y1: f64, x2: f64, y2: f64, radius: f64, wall_colour: &str) -> String {
+    format!(
+        "<path d=\"M {x1:.2} {y1:.2} A {radius:.2} {radius:.2} 0 0 1 {x2:.2} {y2:.2}\" stroke=\"{wall_colour}\" fill=\"none\" />"
+    )
+}
+
+pub(crate) fn svg_line(x1: i32, y1: i32, x2: i32, y2: i32, wall_colour: &str) -> String {
+    format!(
+        "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" stroke=\"{wall_colour}\" stroke-linecap=\"square\" />"
+    )
 }
 
 // separate so can be overridden for different types of grid
@@ -67,29 +571,97 @@ pub trait GridSetup {
         map
     }
 
+    // cells missing from the map (eg masked out) are never anyone's neighbour
     fn configure_cells(map: &mut Vec<Cell>, width: i32, height: i32) {
+        Self::configure_topology(map, width, height, Topology::Plane);
+    }
+
+    // as configure_cells, but steps off a joined edge come back in on the
+    // other side. a cell is never its own neighbour, which a one cell wide
+    // cylinder would otherwise be
+    fn configure_topology(map: &mut [Cell], width: i32, height: i32, topology: Topology) {
+        let positions: HashSet<Position> = map.iter().map(|cell| cell.position).collect();
+
         for cell in map.iter_mut() {
             let row = cell.position.y;
             let col = cell.position.x;
+            let steps = [
+                (Direction::North, col, row - 1),
+                (Direction::South, col, row + 1),
+                (Direction::West, col - 1, row),
+                (Direction::East, col + 1, row),
+            ];
 
-            if row > 0 {
-                cell.neighbours
-                    .insert(Direction::North, Position { x: col, y: row - 1 });
-            }
-
-            if row < height - 1 {
-                cell.neighbours
-                    .insert(Direction::South, Position { x: col, y: row + 1 });
-            }
+            // plain steps first. on a grid two cells across, the wrapped
+            // step reaches the same cell as the plain one, and the cell
+            // should only be a neighbour once, the ordinary way
+            let inside = |x: i32, y: i32| x >= 0 && y >= 0 && x < width && y < height;
+            let (plain, wrapped): (Vec<_>, Vec<_>) =
+                steps.into_iter().partition(|(_, x, y)| inside(*x, *y));
 
-            if col > 0 {
-                cell.neighbours
-                    .insert(Direction::West, Position { x: col - 1, y: row });
-            }
-            if col < width - 1 {
-                cell.neighbours
-                    .insert(Direction::East, Position { x: col + 1, y: row });
+            for (direction, x, y) in plain.into_iter().chain(wrapped) {
+                if let Some(pos) = topology.wrap(x, y, width, height)
+                    && pos != cell.position
+                    && positions.contains(&pos)
+                    && !cell.neighbours.values().any(|neighbour| *neighbour == pos)
+                {
+                    cell.neighbours.insert(direction, pos);
+                }
             }
         }
     }
 }
+
+// a straight wall's two ends, in drawing coordinates
+#[cfg(test)]
+pub(crate) type Wall = ((f64, f64), (f64, f64));
+
+// each `<line ... />` in a drawing, for tests to check which walls are there
+#[cfg(test)]
+fn svg_lines(svg: &str) -> Vec<&str> {
+    svg.split_inclusive("/>")
+        .filter_map(|part| part.find("<line").map(|start| &part[start..]))
+        .collect()
+}
+
+// the ends of each line in a drawing
+#[cfg(test)]
+pub(crate) fn svg_walls(svg: &str) -> Vec<Wall> {
+    let attr = |line: &str, name: &str| -> f64 {
+        let (_, rest) = line
+            .split_once(&format!(" {}=\"", name))
+            .expect("Missing attribute");
+        let (value, _) = rest.split_once('"').expect("Unclosed attribute");
+        value.parse().expect("Attribute isn't a number")
+    };
+    svg_lines(svg)
+        .into_iter()
+        .map(|line| {
+            (
+                (attr(line, "x1"), attr(line, "y1")),
+                (attr(line, "x2"), attr(line, "y2")),
+            )
+        })
+        .collect()
+}
+
+// the walls drawn in `closed` but not in `open`. linking cells should only
+// ever take walls away, so every wall in `open` has to be in `closed` too
+#[cfg(test)]
+pub(crate) fn removed_walls(closed: &str, open: &str) -> Vec<Wall> {
+    let closed = svg_walls(closed);
+    let open = svg_walls(open);
+    assert!(open.iter().all(|wall| closed.contains(wall)));
+    closed
+        .into_iter()
+        .filter(|wall| !open.contains(wall))
+        .collect()
+}
+
+// true if the wall runs between the two points, either way round, allowing
+// for the rounding in the drawing
+#[cfg(test)]
+pub(crate) fn joins(wall: &Wall, a: (f64, f64), b: (f64, f64)) -> bool {
+    let near = |p: (f64, f64), q: (f64, f64)| (p.0 - q.0).abs() < 0.01 && (p.1 - q.1).abs() < 0.01;
+    (near(wall.0, a) && near(wall.1, b)) || (near(wall.0, b) && near(wall.1, a))
+}
@@ -18,20 +18,11 @@ impl Cell {
         }
     }
 
-    // TODO link bi-directional
-    // pub fn link(&mut self, cell_pos: &Position) {
-    //     self.links.insert(*cell_pos);
-    // }
-
-    // pub fn unlink(&mut self, cell_pos: &Position) {
-    //     self.links.remove(cell_pos);
-    // }
-
-    // pub fn is_linked(&self, pos: &Position) -> bool {
-    //     self.links.contains(pos)
-    // }
-
-    pub fn _get_neighbours(&self) -> Vec<&Position> {
-        self.neighbours.values().collect()
+    // sorted by direction so seeded generators pick the same neighbour
+    // every run (HashMap iteration order is random)
+    pub fn get_neighbours(&self) -> Vec<Position> {
+        let mut neighbours: Vec<(&Direction, &Position)> = self.neighbours.iter().collect();
+        neighbours.sort_by_key(|(direction, _)| *direction);
+        neighbours.into_iter().map(|(_, pos)| *pos).collect()
     }
 }
@@ -0,0 +1,112 @@
+use std::{collections::HashMap, hash::Hash};
+
+// union-find over any copyable key, with path compression and union by rank.
+// used by kruskal's to track which cells are already connected
+#[derive(Debug, Clone, Default)]
+pub struct DisjointSet<T: Eq + Hash + Copy> {
+    parents: HashMap<T, T>,
+    ranks: HashMap<T, usize>,
+}
+
+impl<T: Eq + Hash + Copy> DisjointSet<T> {
+    pub fn new() -> Self {
+        Self {
+            parents: HashMap::new(),
+            ranks: HashMap::new(),
+        }
+    }
+
+    // adds an item in a set of its own. does nothing if it's already known
+    pub fn add(&mut self, item: T) {
+        self.parents.entry(item).or_insert(item);
+        self.ranks.entry(item).or_insert(0);
+    }
+
+    // root of the set containing the item, or None if it was never added
+    pub fn find(&mut self, item: T) -> Option<T> {
+        let mut root = *self.parents.get(&item)?;
+        while self.parents[&root] != root {
+            root = self.parents[&root];
+        }
+
+        // point everything on the way straight at the root
+        let mut current = item;
+        while current != root {
+            let next = self.parents[&current];
+            self.parents.insert(current, root);
+            current = next;
+        }
+
+        Some(root)
+    }
+
+    // merges the sets containing both items, adding either if needed.
+    // returns false if they were already in the same set
+    pub fn union(&mut self, a: T, b: T) -> bool {
+        self.add(a);
+        self.add(b);
+        let root_a = self.find(a).expect("Item was just added");
+        let root_b = self.find(b).expect("Item was just added");
+
+        if root_a == root_b {
+            return false;
+        }
+
+        let rank_a = self.ranks[&root_a];
+        let rank_b = self.ranks[&root_b];
+        if rank_a < rank_b {
+            self.parents.insert(root_a, root_b);
+        } else {
+            self.parents.insert(root_b, root_a);
+            if rank_a == rank_b {
+                self.ranks.insert(root_a, rank_a + 1);
+            }
+        }
+
+        true
+    }
+
+    pub fn connected(&mut self, a: T, b: T) -> bool {
+        match (self.find(a), self.find(b)) {
+            (Some(root_a), Some(root_b)) => root_a == root_b,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Position;
+
+    use super::DisjointSet;
+
+    #[test]
+    fn it_should_merge_sets() {
+        let a = Position { x: 0, y: 0 };
+        let b = Position { x: 1, y: 0 };
+        let c = Position { x: 2, y: 0 };
+        let d = Position { x: 3, y: 0 };
+
+        let mut sets = DisjointSet::new();
+        for pos in [a, b, c, d] {
+            sets.add(pos);
+        }
+
+        assert!(!sets.connected(a, b));
+        assert!(sets.union(a, b));
+        assert!(sets.union(c, d));
+        assert!(sets.connected(a, b));
+        assert!(!sets.connected(b, c));
+
+        assert!(sets.union(b, d));
+        assert!(sets.connected(a, c));
+        // already joined
+        assert!(!sets.union(a, d));
+    }
+
+    #[test]
+    fn it_should_not_find_unknown_items() {
+        let mut sets: DisjointSet<Position> = DisjointSet::new();
+        assert_eq!(sets.find(Position { x: 0, y: 0 }), None);
+    }
+}
@@ -1,30 +1,134 @@
-use std::{collections::HashMap, vec};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    error::Error,
+    fmt::{self, Display, Formatter},
+    vec,
+};
 
-use crate::{Position, grid::Grid};
+use crate::{Position, grid::Grid, weights::Weights};
 
-pub fn distances(
-    root: &Position,
-    links: &HashMap<Position, Vec<Position>>,
-) -> HashMap<Position, i32> {
-    let mut distances = HashMap::new();
-    distances.insert(*root, 0);
-    let mut frontier = vec![root];
+// how far each cell is from a root cell, and which cell it was reached from
+// on the way, so the route back to the root can be retraced. either covers
+// everywhere reachable from the root, or just the cells along one path
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Distances {
+    root: Position,
+    cells: HashMap<Position, i32>,
+    previous: HashMap<Position, Position>,
+    path: bool,
+}
+
+impl Distances {
+    pub fn new(root: Position) -> Self {
+        Distances {
+            root,
+            cells: HashMap::from([(root, 0)]),
+            previous: HashMap::new(),
+            path: false,
+        }
+    }
+
+    // each cell's distance is how far along the path it is
+    pub fn along(path: &[Position]) -> Option<Self> {
+        let mut distances = Distances::new(*path.first()?);
+        for (distance, step) in path.windows(2).enumerate() {
+            distances.insert(step[1], distance as i32 + 1, step[0]);
+        }
+        distances.path = true;
+        Some(distances)
+    }
+
+    pub fn root(&self) -> Position {
+        self.root
+    }
+
+    // true if this only covers one path, from `along`
+    pub fn is_path(&self) -> bool {
+        self.path
+    }
+
+    pub fn get(&self, pos: &Position) -> Option<i32> {
+        self.cells.get(pos).copied()
+    }
+
+    pub fn contains(&self, pos: &Position) -> bool {
+        self.cells.contains_key(pos)
+    }
+
+    // number of cells reached, including the root
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    // records that pos is `distance` away, reached from `from`
+    pub fn insert(&mut self, pos: Position, distance: i32, from: Position) {
+        self.cells.insert(pos, distance);
+        self.previous.insert(pos, from);
+    }
+
+    // the furthest cell and its distance. ties go to the cell nearest the
+    // top left, so the answer doesn't depend on hash order
+    pub fn max(&self) -> (Position, i32) {
+        self.cells
+            .iter()
+            .max_by_key(|(pos, distance)| (**distance, Reverse((pos.y, pos.x))))
+            .map(|(pos, distance)| (*pos, *distance))
+            .unwrap_or((self.root, 0))
+    }
+
+    // the route from the root to goal, both included. None if goal wasn't
+    // reached, or if what was inserted doesn't lead back to the root
+    pub fn path_to(&self, goal: &Position) -> Option<Vec<Position>> {
+        if !self.contains(goal) {
+            return None;
+        }
+
+        let mut path = vec![*goal];
+        let mut current = *goal;
+        while current != self.root {
+            // longer than every cell means it's gone round in a loop
+            if path.len() > self.len() {
+                return None;
+            }
+            current = *self.previous.get(&current)?;
+            path.push(current);
+        }
+        path.reverse();
+        Some(path)
+    }
 
-    while frontier.len() > 0 {
+    // nearest first, ties in row order
+    pub fn iter(&self) -> impl Iterator<Item = (Position, i32)> {
+        let mut cells: Vec<(Position, i32)> = self
+            .cells
+            .iter()
+            .map(|(pos, distance)| (*pos, *distance))
+            .collect();
+        cells.sort_by_key(|(pos, distance)| (*distance, pos.y, pos.x));
+        cells.into_iter()
+    }
+}
+
+pub fn distances(root: &Position, links: &HashMap<Position, Vec<Position>>) -> Distances {
+    let mut distances = Distances::new(*root);
+    let mut frontier = vec![*root];
+
+    while !frontier.is_empty() {
         let mut new_frontier = vec![];
 
-        // TODO links are added in row/col order
-        // so iterate over all cells and check
         for pos in frontier {
-            // get vec from pos
-            if let Some(cell_links) = links.get(&pos) {
-                for link in cell_links {
-                    if distances.contains_key(&link) {
-                        continue;
-                    }
-                    distances.insert(*link, distances[&pos] + 1);
-                    new_frontier.push(link);
+            let distance = distances.get(&pos).unwrap_or(0);
+            for link in links.get(&pos).into_iter().flatten() {
+                if distances.contains(link) {
+                    continue;
                 }
+                distances.insert(*link, distance + 1, pos);
+                new_frontier.push(*link);
             }
         }
         frontier = new_frontier;
@@ -33,37 +137,172 @@ pub fn distances(
     distances
 }
 
-pub fn path_to(goal: &Position, grid: &Box<dyn Grid>) -> HashMap<Position, i32> {
-    let mut current = goal;
-    let root = grid.map()[0].position;
+// like `distances`, but each step costs the weight of the cell it moves
+// into, so the nearest cell isn't always the fewest steps away
+pub fn weighted_distances(
+    root: &Position,
+    links: &HashMap<Position, Vec<Position>>,
+    weights: &Weights,
+) -> Distances {
+    dijkstra(root, links, weights, None)
+}
 
-    // from goal
-    // check links
-    // if links < goal distance
-    // change current
+// cheapest cells come off the heap first; ties go in row order to keep it
+// repeatable. stops as soon as the goal comes off, if there is one. costs
+// saturate rather than overflow, so very heavy paths all cost i32::MAX
+fn dijkstra(
+    root: &Position,
+    links: &HashMap<Position, Vec<Position>>,
+    weights: &Weights,
+    goal: Option<&Position>,
+) -> Distances {
+    let mut distances = Distances::new(*root);
+    let mut frontier = BinaryHeap::from([Reverse((0, root.y, root.x))]);
 
-    let calculated = distances(&root, grid.links());
-    let mut breadcrumbs: HashMap<Position, i32> = HashMap::new();
+    while let Some(Reverse((cost, y, x))) = frontier.pop() {
+        let pos = Position { x, y };
+        if goal == Some(&pos) {
+            break;
+        }
+        // already reached more cheaply since this was queued
+        if distances.get(&pos).is_some_and(|best| cost > best) {
+            continue;
+        }
 
-    if let Some(goal_distance) = calculated.get(goal) {
-        breadcrumbs.insert(*goal, *goal_distance);
+        for link in links.get(&pos).into_iter().flatten() {
+            let next_cost = cost.saturating_add(weights.get(link));
+            if distances.get(link).is_none_or(|best| next_cost < best) {
+                distances.insert(*link, next_cost, pos);
+                frontier.push(Reverse((next_cost, link.y, link.x)));
+            }
+        }
     }
 
-    while *current != root {
-        if let Some(neighbours) = grid.links().get(current) {
-            neighbours.iter().for_each(|neighbour| {
-                let neighbour_distance = calculated.get(neighbour).unwrap();
-                let current_distance = calculated.get(current).unwrap();
+    distances
+}
 
-                if neighbour_distance < current_distance {
-                    breadcrumbs.insert(*neighbour, *neighbour_distance);
-                    current = neighbour;
-                }
-            });
+// the two cells furthest apart in a maze, and the path between them
+#[derive(Debug, Eq, PartialEq)]
+pub struct LongestPath {
+    pub start: Position,
+    pub goal: Position,
+    pub path: Vec<Position>,
+    // steps from start to goal, or if weighted, the weights of every cell on
+    // the path, both ends included
+    pub cost: i32,
+}
+
+// the furthest cell from any cell is one end of the longest path, so search
+// again from there to find the other. only covers the part of the maze
+// connected to the first cell. None if the grid has no cells
+pub fn longest_path(grid: &dyn Grid) -> Option<LongestPath> {
+    longest(grid, |root| distances(root, grid.links()))
+}
+
+// the most expensive path rather than the most steps. walking it costs the
+// weight of each cell entered, which differs with the direction when the
+// ends weigh different amounts, so the cost counts the start cell too and is
+// the same either way round. with that, the same two searches are exact for
+// perfect mazes, like `longest_path`
+pub fn longest_weighted_path(grid: &dyn Grid, weights: &Weights) -> Option<LongestPath> {
+    let mut longest = longest(grid, |root| weighted_distances(root, grid.links(), weights))?;
+    longest.cost = longest.cost.saturating_add(weights.get(&longest.start));
+    Some(longest)
+}
+
+fn longest(grid: &dyn Grid, from: impl Fn(&Position) -> Distances) -> Option<LongestPath> {
+    let first = grid.map().first()?.position;
+    let (start, _) = from(&first).max();
+    let from_start = from(&start);
+    let (goal, cost) = from_start.max();
+    let path = from_start.path_to(&goal)?;
+
+    Some(LongestPath {
+        start,
+        goal,
+        path,
+        cost,
+    })
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum PathError {
+    // the position isn't a cell in the grid
+    NotInGrid(Position),
+    // no passages lead from the start to the goal
+    Unreachable(Position, Position),
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::NotInGrid(pos) => write!(f, "{:?} isn't in the grid", pos),
+            PathError::Unreachable(start, goal) => {
+                write!(f, "there's no path from {:?} to {:?}", start, goal)
+            }
         }
     }
+}
 
-    breadcrumbs
+impl Error for PathError {}
+
+// the shortest path from start to goal, both included. searches outwards from
+// the start and stops as soon as it reaches the goal
+pub fn solve(
+    grid: &dyn Grid,
+    start: &Position,
+    goal: &Position,
+) -> Result<Vec<Position>, PathError> {
+    for pos in [start, goal] {
+        if grid.cell_at(pos).is_none() {
+            return Err(PathError::NotInGrid(*pos));
+        }
+    }
+
+    // the cell each one was first reached from
+    let mut came_from = HashMap::from([(*start, *start)]);
+    let mut frontier = VecDeque::from([*start]);
+
+    while let Some(pos) = frontier.pop_front() {
+        if pos == *goal {
+            let mut path = vec![pos];
+            let mut current = pos;
+            while current != *start {
+                current = came_from[&current];
+                path.push(current);
+            }
+            path.reverse();
+            return Ok(path);
+        }
+
+        for link in grid.links().get(&pos).into_iter().flatten() {
+            if !came_from.contains_key(link) {
+                came_from.insert(*link, pos);
+                frontier.push_back(*link);
+            }
+        }
+    }
+
+    Err(PathError::Unreachable(*start, *goal))
+}
+
+// the cheapest path from start to goal, both included, where each step costs
+// the weight of the cell it moves into
+pub fn solve_weighted(
+    grid: &dyn Grid,
+    start: &Position,
+    goal: &Position,
+    weights: &Weights,
+) -> Result<Vec<Position>, PathError> {
+    for pos in [start, goal] {
+        if grid.cell_at(pos).is_none() {
+            return Err(PathError::NotInGrid(*pos));
+        }
+    }
+
+    dijkstra(start, grid.links(), weights, Some(goal))
+        .path_to(goal)
+        .ok_or(PathError::Unreachable(*start, *goal))
 }
 
 #[cfg(test)]
@@ -77,9 +316,13 @@ mod test {
         Position,
         algos::sidewinder::sidewinder,
         grid::{Grid, StandardGrid},
+        weights::Weights,
     };
 
-    use super::distances;
+    use super::{
+        Distances, PathError, distances, longest_path, longest_weighted_path, solve,
+        solve_weighted, weighted_distances,
+    };
 
     #[test]
     fn it_should_find_distances() {
@@ -88,7 +331,7 @@ mod test {
         let mut rng: SmallRng = Seeder::from(&seed).into_rng();
         sidewinder(&mut grid, &mut rng);
 
-        let distances = distances(&grid.map()[0].position, &grid.links());
+        let distances = distances(&grid.map()[0].position, grid.links());
 
         let expected = HashMap::from([
             (Position { x: 2, y: 1 }, 7),
@@ -108,6 +351,195 @@ mod test {
             (Position { x: 0, y: 3 }, 9),
             (Position { x: 0, y: 1 }, 11),
         ]);
-        assert_eq!(distances, expected);
+        assert_eq!(distances.iter().collect::<HashMap<_, _>>(), expected);
+        assert_eq!(distances.root(), Position { x: 0, y: 0 });
+        assert_eq!(distances.max(), (Position { x: 0, y: 1 }, 11));
+        assert_eq!(distances.get(&Position { x: 3, y: 2 }), Some(5));
+
+        // nearest first
+        let order: Vec<i32> = distances.iter().map(|(_, distance)| distance).collect();
+        assert!(order.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        let path = distances.path_to(&Position { x: 2, y: 1 }).unwrap();
+        assert_eq!(path.len(), 8);
+        assert_eq!(path[0], Position { x: 0, y: 0 });
+        let along = Distances::along(&path).unwrap();
+        assert_eq!(along.max().1, 7);
+        assert!(along.is_path());
+        assert!(!distances.is_path());
+    }
+
+    #[test]
+    fn it_should_not_follow_broken_breadcrumbs() {
+        let root = Position { x: 0, y: 0 };
+        let a = Position { x: 1, y: 0 };
+        let b = Position { x: 2, y: 0 };
+
+        // reached from a cell that was never reached itself
+        let mut distances = Distances::new(root);
+        distances.insert(a, 1, Position { x: 5, y: 5 });
+        assert_eq!(distances.path_to(&a), None);
+
+        // reached from each other
+        let mut distances = Distances::new(root);
+        distances.insert(a, 1, b);
+        distances.insert(b, 2, a);
+        assert_eq!(distances.path_to(&b), None);
+    }
+
+    #[test]
+    fn it_should_find_weighted_distances() {
+        // a 2x2 loop, so there are two ways round to the far corner
+        let mut grid = StandardGrid::new(2, 2);
+        let corners = [(0, 0), (1, 0), (1, 1), (0, 1), (0, 0)];
+        for pair in corners.windows(2) {
+            let a = Position {
+                x: pair[0].0,
+                y: pair[0].1,
+            };
+            let b = Position {
+                x: pair[1].0,
+                y: pair[1].1,
+            };
+            grid.link(&a, &b);
+        }
+        let start = Position { x: 0, y: 0 };
+        let lava = Position { x: 0, y: 1 };
+        let goal = Position { x: 1, y: 1 };
+
+        // with no weights it's the same as counting steps, and the tie
+        // goes through the top row
+        let mut weights = Weights::new();
+        let unweighted = weighted_distances(&start, grid.links(), &weights);
+        assert_eq!(unweighted, distances(&start, grid.links()));
+        assert_eq!(
+            unweighted.path_to(&goal),
+            Some(vec![start, Position { x: 1, y: 0 }, goal])
+        );
+
+        weights.set(&Position { x: 1, y: 0 }, 10);
+        weights.set(&lava, 3);
+        let weighted = weighted_distances(&start, grid.links(), &weights);
+        assert_eq!(weighted.get(&goal), Some(4));
+        assert_eq!(weighted.get(&Position { x: 1, y: 0 }), Some(10));
+        assert_eq!(weighted.path_to(&goal), Some(vec![start, lava, goal]));
+        assert_eq!(weighted.max(), (Position { x: 1, y: 0 }, 10));
+
+        assert_eq!(
+            solve_weighted(&grid, &start, &goal, &weights),
+            Ok(vec![start, lava, goal])
+        );
+    }
+
+    #[test]
+    fn it_should_not_overflow_on_heavy_cells() {
+        let mut grid: Box<dyn Grid> = Box::new(StandardGrid::new(3, 1));
+        let start = Position { x: 0, y: 0 };
+        let middle = Position { x: 1, y: 0 };
+        let goal = Position { x: 2, y: 0 };
+        grid.link(&start, &middle);
+        grid.link(&middle, &goal);
+
+        let mut weights = Weights::new();
+        weights.set(&middle, i32::MAX);
+        weights.set(&goal, i32::MAX);
+        let weighted = weighted_distances(&start, grid.links(), &weights);
+        assert_eq!(weighted.get(&goal), Some(i32::MAX));
+        assert_eq!(
+            solve_weighted(grid.as_ref(), &start, &goal, &weights),
+            Ok(vec![start, middle, goal])
+        );
+    }
+
+    #[test]
+    fn it_should_solve_between_any_cells() {
+        let mut grid: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        sidewinder(&mut grid, &mut rng);
+
+        let start = Position { x: 1, y: 3 };
+        let goal = Position { x: 0, y: 1 };
+        let path = solve(grid.as_ref(), &start, &goal).unwrap();
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        assert_eq!(path.len(), 4);
+        for step in path.windows(2) {
+            assert!(grid.is_linked(&step[0], &step[1]));
+        }
+
+        assert_eq!(solve(grid.as_ref(), &goal, &goal), Ok(vec![goal]));
+        let outside = Position { x: 4, y: 0 };
+        assert_eq!(
+            solve(grid.as_ref(), &start, &outside),
+            Err(PathError::NotInGrid(outside))
+        );
+    }
+
+    #[test]
+    fn it_should_not_hang_on_unreachable_goals() {
+        // no passages at all
+        let grid: Box<dyn Grid> = Box::new(StandardGrid::new(3, 3));
+        let start = Position { x: 0, y: 0 };
+        let goal = Position { x: 2, y: 2 };
+
+        assert_eq!(
+            solve(grid.as_ref(), &start, &goal),
+            Err(PathError::Unreachable(start, goal))
+        );
+        assert_eq!(distances(&start, grid.links()).path_to(&goal), None);
+    }
+
+    #[test]
+    fn it_should_find_the_longest_path() {
+        let mut grid: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        sidewinder(&mut grid, &mut rng);
+
+        let longest = longest_path(grid.as_ref()).unwrap();
+        assert_eq!(longest.start, Position { x: 0, y: 1 });
+        assert_eq!(longest.goal, Position { x: 0, y: 0 });
+        assert_eq!(longest.path.len(), 12);
+        assert_eq!(longest.cost, 11);
+
+        // with no weights it's the same path, costing one per cell on it,
+        // and a heavy cell on the way costs more without moving the ends
+        let mut weights = Weights::new();
+        let unweighted = longest_weighted_path(grid.as_ref(), &weights).unwrap();
+        assert_eq!(unweighted.path, longest.path);
+        assert_eq!(unweighted.cost, 12);
+        weights.set(&Position { x: 3, y: 2 }, 10);
+        let weighted = longest_weighted_path(grid.as_ref(), &weights).unwrap();
+        assert_eq!(weighted.start, Position { x: 0, y: 1 });
+        assert_eq!(weighted.goal, Position { x: 0, y: 0 });
+        assert_eq!(weighted.cost, 21);
+
+        // a single cell is its own longest path
+        let grid: Box<dyn Grid> = Box::new(StandardGrid::new(1, 1));
+        let longest = longest_path(grid.as_ref()).unwrap();
+        assert_eq!(longest.path, vec![Position { x: 0, y: 0 }]);
+    }
+
+    #[test]
+    fn it_should_cost_the_longest_path_the_same_either_way_round() {
+        // a corridor a - b - c. with a heavy, c to a costs 101 to walk and
+        // a to c only 2, so both ends count and it's 102 whichever is heavy
+        let a = Position { x: 0, y: 0 };
+        let b = Position { x: 1, y: 0 };
+        let c = Position { x: 2, y: 0 };
+        let mut grid: Box<dyn Grid> = Box::new(StandardGrid::new(3, 1));
+        grid.link(&a, &b);
+        grid.link(&b, &c);
+
+        for heavy in [a, c] {
+            let mut weights = Weights::new();
+            weights.set(&heavy, 100);
+            let longest = longest_weighted_path(grid.as_ref(), &weights).unwrap();
+            assert_eq!(longest.cost, 102);
+            let mut ends = [longest.start, longest.goal];
+            ends.sort_by_key(|pos| pos.x);
+            assert_eq!(ends, [a, c]);
+        }
     }
 }
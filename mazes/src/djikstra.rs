@@ -4,8 +4,9 @@ use crate::{
     Position,
     base_grid::{GridSetup, Svg},
     cell::Cell,
-    distances::distances,
+    distances::{Distances, weighted_distances},
     grid::Grid,
+    weights::Weights,
 };
 
 #[derive(Debug, Eq, PartialEq)]
@@ -14,7 +15,8 @@ pub struct DjikstraGrid {
     pub width: i32,
     pub height: i32,
     pub links: HashMap<Position, Vec<Position>>,
-    distances: HashMap<Position, i32>,
+    distances: Option<Distances>,
+    weights: Weights,
 }
 
 impl DjikstraGrid {
@@ -27,9 +29,26 @@ impl DjikstraGrid {
             width,
             height,
             links,
-            distances: HashMap::new(),
+            distances: None,
+            weights: Weights::new(),
         }
     }
+
+    // distances count the weight of each cell moved into rather than steps
+    pub fn set_weights(&mut self, weights: Weights) {
+        self.weights = weights;
+        if !self.links.is_empty() {
+            self.flood();
+        }
+    }
+
+    // distances from the first cell. a grid with no cells has none
+    fn flood(&mut self) {
+        self.distances = self
+            .map
+            .first()
+            .map(|first| weighted_distances(&first.position, &self.links, &self.weights));
+    }
 }
 
 impl Grid for DjikstraGrid {
@@ -49,24 +68,27 @@ impl Grid for DjikstraGrid {
         &mut self.links
     }
     fn set_links(&mut self, links: HashMap<Position, Vec<Position>>) {
-        let distances = distances(&self.map[0].position, &self.links());
-        self.distances = distances;
         self.links = links;
+        self.flood();
     }
 
-    fn distances(&self) -> HashMap<Position, i32> {
-        self.distances.clone()
+    fn distances(&self) -> Option<&Distances> {
+        self.distances.as_ref()
     }
 
-    fn set_distances(&mut self, distances: HashMap<Position, i32>) {
-        self.distances = distances;
+    fn set_distances(&mut self, distances: Distances) {
+        self.distances = Some(distances);
     }
 
+    // a path is marked out with `*`, anything else shows each cell's
+    // distance in base 36
     fn contents_of(&self, cell: &Cell) -> String {
-        dbg!(&cell.position, self.distances.get(&cell.position));
-
-        match self.distances.get(&cell.position) {
-            Some(num) => match std::char::from_digit(*num as u32, 36) {
+        let Some(distances) = self.distances.as_ref() else {
+            return String::from(" ");
+        };
+        match distances.get(&cell.position) {
+            Some(_) if distances.is_path() => String::from("*"),
+            Some(num) => match std::char::from_digit(num as u32, 36) {
                 Some(ch) => ch.to_string(),
                 None => String::from(" "),
             },
@@ -78,3 +100,78 @@ impl Grid for DjikstraGrid {
 impl GridSetup for DjikstraGrid {}
 
 impl Svg for DjikstraGrid {}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::{
+        Position,
+        distances::Distances,
+        grid::{Grid, StandardGrid},
+        weights::Weights,
+    };
+
+    use super::DjikstraGrid;
+
+    #[test]
+    fn it_should_show_distances_and_paths_differently() {
+        // a single corridor along the top, with the bottom row cut off
+        let mut links = HashMap::new();
+        let mut corridor: Box<dyn Grid> = Box::new(StandardGrid::new(3, 2));
+        corridor.link(&Position { x: 0, y: 0 }, &Position { x: 1, y: 0 });
+        corridor.link(&Position { x: 1, y: 0 }, &Position { x: 2, y: 0 });
+        links.extend(corridor.links().clone());
+
+        let mut grid: Box<dyn Grid> = Box::new(DjikstraGrid::new(3, 2));
+        grid.set_links(links);
+        assert_eq!(
+            format!("{}", grid),
+            "+---+---+---+
+| 0   1   2 |
++---+---+---+
+|   |   |   |
++---+---+---+
+"
+        );
+
+        let path = [Position { x: 1, y: 0 }, Position { x: 2, y: 0 }];
+        grid.set_distances(Distances::along(&path).unwrap());
+        assert_eq!(
+            format!("{}", grid),
+            "+---+---+---+
+|     *   * |
++---+---+---+
+|   |   |   |
++---+---+---+
+"
+        );
+    }
+
+    #[test]
+    fn it_should_show_weighted_distances() {
+        let mut corridor: Box<dyn Grid> = Box::new(StandardGrid::new(3, 1));
+        corridor.link(&Position { x: 0, y: 0 }, &Position { x: 1, y: 0 });
+        corridor.link(&Position { x: 1, y: 0 }, &Position { x: 2, y: 0 });
+
+        let mut grid = DjikstraGrid::new(3, 1);
+        grid.set_links(corridor.links().clone());
+        let mut weights = Weights::new();
+        weights.set(&Position { x: 1, y: 0 }, 5);
+        grid.set_weights(weights);
+
+        let grid: Box<dyn Grid> = Box::new(grid);
+        assert_eq!(
+            format!("{}", grid),
+            "+---+---+---+\n| 0   5   6 |\n+---+---+---+\n"
+        );
+    }
+
+    #[test]
+    fn it_should_have_no_distances_without_cells() {
+        let mut grid = DjikstraGrid::new(0, 0);
+        grid.set_links(HashMap::new());
+        grid.set_weights(Weights::new());
+        assert!(grid.distances().is_none());
+    }
+}
@@ -0,0 +1,436 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+use rand::{Rng, rngs::SmallRng};
+
+use crate::{
+    Direction, Position, Shape, base_grid::Svg, cell::Cell, distances::Distances, grid::Grid,
+};
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum GraphError {
+    // an edge names a cell that isn't in the graph
+    UnknownCell(usize),
+    // an edge joins a cell to itself
+    SelfLoop(usize),
+    // a polygon's sides don't each say what's across them
+    MissingSides(usize),
+}
+
+impl Display for GraphError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::UnknownCell(index) => write!(f, "there's no cell {}", index),
+            GraphError::SelfLoop(index) => write!(f, "cell {} can't neighbour itself", index),
+            GraphError::MissingSides(index) => {
+                write!(
+                    f,
+                    "cell {} needs a neighbour (or none) for each side",
+                    index
+                )
+            }
+        }
+    }
+}
+
+impl Error for GraphError {}
+
+// cells are the nodes of a graph and neighbours are its edges, so there's no
+// layout to speak of. cell `i` is at `Position { x: i, y: 0 }`, and each can
+// have a polygon to draw it with. width and height are the size of the area
+// the polygons cover, in cells
+#[derive(Debug, PartialEq)]
+pub struct GraphGrid {
+    pub map: Vec<Cell>,
+    pub width: i32,
+    pub height: i32,
+    pub links: HashMap<Position, Vec<Position>>,
+    pub distances: Option<Distances>,
+    pub polygons: Vec<Vec<(f64, f64)>>,
+    // for each side of each polygon, the cell across it
+    pub sides: Vec<Vec<Option<Position>>>,
+}
+
+impl GraphGrid {
+    // one cell per polygon, joined by `edges` of polygon indices. polygons
+    // that share a side should have the same corners, so the wall between
+    // them is only drawn when they aren't linked. use `from_sides` if you
+    // already know which cell is across each side
+    pub fn from_adjacency(
+        polygons: Vec<Vec<(f64, f64)>>,
+        edges: &[(usize, usize)],
+    ) -> Result<Self, GraphError> {
+        for (a, b) in edges {
+            for index in [a, b] {
+                if *index >= polygons.len() {
+                    return Err(GraphError::UnknownCell(*index));
+                }
+            }
+            if a == b {
+                return Err(GraphError::SelfLoop(*a));
+            }
+        }
+
+        let mut joined = vec![vec![]; polygons.len()];
+        for (a, b) in edges {
+            joined[*a].push(*b);
+            joined[*b].push(*a);
+        }
+
+        // only sides between cells an edge joins are shared, so cells that
+        // happen to touch without an edge keep their wall
+        let same = |p: (f64, f64), q: (f64, f64)| {
+            (p.0 - q.0).abs() <= MIN_SIDE && (p.1 - q.1).abs() <= MIN_SIDE
+        };
+        let sides = polygons
+            .iter()
+            .enumerate()
+            .map(|(index, polygon)| {
+                (0..polygon.len())
+                    .map(|i| {
+                        let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+                        joined[index].iter().copied().find(|other| {
+                            let corners = &polygons[*other];
+                            corners.iter().any(|c| same(*c, a))
+                                && corners.iter().any(|c| same(*c, b))
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(Self::build(polygons, edges, sides))
+    }
+
+    // one cell per polygon, where each side names the polygon across it
+    // (None for the outside). cells are neighbours if either one has a side
+    // facing the other
+    pub fn from_sides(
+        polygons: Vec<Vec<(f64, f64)>>,
+        sides: Vec<Vec<Option<usize>>>,
+    ) -> Result<Self, GraphError> {
+        let mut edges = vec![];
+        for (index, polygon) in polygons.iter().enumerate() {
+            let cell_sides = sides
+                .get(index)
+                .filter(|cell_sides| cell_sides.len() == polygon.len())
+                .ok_or(GraphError::MissingSides(index))?;
+            for other in cell_sides.iter().flatten() {
+                if *other >= polygons.len() {
+                    return Err(GraphError::UnknownCell(*other));
+                }
+                if *other == index {
+                    return Err(GraphError::SelfLoop(index));
+                }
+                edges.push((index.min(*other), index.max(*other)));
+            }
+        }
+        edges.sort();
+        edges.dedup();
+
+        Ok(Self::build(polygons, &edges, sides))
+    }
+
+    // edges and sides have already been checked
+    fn build(
+        polygons: Vec<Vec<(f64, f64)>>,
+        edges: &[(usize, usize)],
+        sides: Vec<Vec<Option<usize>>>,
+    ) -> Self {
+        let mut map: Vec<Cell> = (0..polygons.len())
+            .map(|index| Cell::new(index as i32, 0))
+            .collect();
+
+        for (a, b) in edges {
+            for (from, to) in [(*a, *b), (*b, *a)] {
+                let to = map[to].position;
+                let cell = &mut map[from];
+                if !cell.neighbours.values().any(|pos| *pos == to) {
+                    let direction = Direction::Adjacent(cell.neighbours.len());
+                    cell.neighbours.insert(direction, to);
+                }
+            }
+        }
+
+        let (width, height) = polygons
+            .iter()
+            .flatten()
+            .fold((0.0_f64, 0.0_f64), |(w, h), (x, y)| (w.max(*x), h.max(*y)));
+
+        let sides = sides
+            .into_iter()
+            .map(|cell_sides| {
+                cell_sides
+                    .into_iter()
+                    .map(|other| other.map(|other| map[other].position))
+                    .collect()
+            })
+            .collect();
+
+        GraphGrid {
+            map,
+            width: width.ceil() as i32,
+            height: height.ceil() as i32,
+            links: HashMap::new(),
+            distances: None,
+            polygons,
+            sides,
+        }
+    }
+
+    // `count` random points in a width x height area, each cell being the
+    // part of the area closer to its point than any other. cells that share
+    // a side are neighbours
+    pub fn voronoi(width: i32, height: i32, count: usize, rng: &mut SmallRng) -> Self {
+        let sites: Vec<(f64, f64)> = (0..count)
+            .map(|_| {
+                (
+                    rng.random_range(0.0..width as f64),
+                    rng.random_range(0.0..height as f64),
+                )
+            })
+            .collect();
+
+        // clipping can leave a side too short to count from one cell but
+        // not the other. they're still neighbours, through the longer side
+        let (polygons, sides) = sites
+            .iter()
+            .enumerate()
+            .map(|(index, site)| voronoi_cell(&sites, index, *site, width, height))
+            .unzip();
+
+        let mut grid = Self::from_sides(polygons, sides).expect("Voronoi sites are all cells");
+        grid.width = width;
+        grid.height = height;
+        grid
+    }
+}
+
+// shortest side worth counting as a wall between two cells
+const MIN_SIDE: f64 = 1e-9;
+
+// clips the whole area down to the points nearer `site` than any other. each
+// side remembers which site's cut made it (None for the area's edge), which
+// is the cell across it
+fn voronoi_cell(
+    sites: &[(f64, f64)],
+    index: usize,
+    site: (f64, f64),
+    width: i32,
+    height: i32,
+) -> (Vec<(f64, f64)>, Vec<Option<usize>>) {
+    let (w, h) = (width as f64, height as f64);
+    // each corner, and what made the side from it to the next corner
+    let mut polygon: Vec<((f64, f64), Option<usize>)> = vec![
+        ((0.0, 0.0), None),
+        ((w, 0.0), None),
+        ((w, h), None),
+        ((0.0, h), None),
+    ];
+
+    for (other, other_site) in sites.iter().enumerate() {
+        if other == index || polygon.is_empty() {
+            continue;
+        }
+
+        let mid = ((site.0 + other_site.0) / 2.0, (site.1 + other_site.1) / 2.0);
+        let normal = (other_site.0 - site.0, other_site.1 - site.1);
+        // positive on the other site's side of the line between them
+        let side = |p: (f64, f64)| (p.0 - mid.0) * normal.0 + (p.1 - mid.1) * normal.1;
+
+        let mut clipped = vec![];
+        for (i, (current, made_by)) in polygon.iter().enumerate() {
+            let (next, _) = polygon[(i + 1) % polygon.len()];
+            let (current_side, next_side) = (side(*current), side(next));
+            let crossing = || {
+                let t = current_side / (current_side - next_side);
+                (
+                    current.0 + t * (next.0 - current.0),
+                    current.1 + t * (next.1 - current.1),
+                )
+            };
+
+            match (current_side <= 0.0, next_side <= 0.0) {
+                (true, true) => clipped.push((*current, *made_by)),
+                (true, false) => {
+                    clipped.push((*current, *made_by));
+                    clipped.push((crossing(), Some(other)));
+                }
+                (false, true) => clipped.push((crossing(), *made_by)),
+                (false, false) => {}
+            }
+        }
+        polygon = clipped;
+    }
+
+    let sides = (0..polygon.len())
+        .map(|i| {
+            let (corner, made_by) = polygon[i];
+            let (next, _) = polygon[(i + 1) % polygon.len()];
+            let length = (next.0 - corner.0).hypot(next.1 - corner.1);
+            made_by.filter(|_| length > MIN_SIDE)
+        })
+        .collect();
+
+    (
+        polygon.into_iter().map(|(corner, _)| corner).collect(),
+        sides,
+    )
+}
+
+impl Grid for GraphGrid {
+    fn map(&self) -> &Vec<Cell> {
+        &self.map
+    }
+    fn width(&self) -> i32 {
+        self.width
+    }
+    fn height(&self) -> i32 {
+        self.height
+    }
+    fn links(&self) -> &HashMap<Position, Vec<Position>> {
+        &self.links
+    }
+    fn links_mut(&mut self) -> &mut HashMap<Position, Vec<Position>> {
+        &mut self.links
+    }
+    fn set_links(&mut self, links: HashMap<Position, Vec<Position>>) {
+        self.links = links;
+    }
+    fn distances(&self) -> Option<&Distances> {
+        self.distances.as_ref()
+    }
+
+    fn set_distances(&mut self, distances: Distances) {
+        self.distances = Some(distances);
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::Graph
+    }
+
+    fn cell_at(&self, pos: &Position) -> Option<&Cell> {
+        if pos.y != 0 || pos.x < 0 {
+            return None;
+        }
+        self.map.get(pos.x as usize)
+    }
+
+    fn polygon(&self, pos: &Position) -> Option<&[(f64, f64)]> {
+        self.cell_at(pos)
+            .and_then(|_| self.polygons.get(pos.x as usize))
+            .map(|polygon| polygon.as_slice())
+    }
+
+    fn polygon_sides(&self, pos: &Position) -> Option<&[Option<Position>]> {
+        self.cell_at(pos)
+            .and_then(|_| self.sides.get(pos.x as usize))
+            .map(|sides| sides.as_slice())
+    }
+}
+
+impl Svg for GraphGrid {}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        Direction, Position, algos::recursive_backtracker::recursive_backtracker, base_grid::Svg,
+        distances::distances, grid::Grid,
+    };
+
+    use super::{GraphError, GraphGrid};
+
+    fn square(x: f64, y: f64) -> Vec<(f64, f64)> {
+        vec![(x, y), (x + 1.0, y), (x + 1.0, y + 1.0), (x, y + 1.0)]
+    }
+
+    #[test]
+    fn it_should_build_from_adjacency() {
+        // a row of three squares
+        let polygons = vec![square(0.0, 0.0), square(1.0, 0.0), square(2.0, 0.0)];
+        let mut grid = GraphGrid::from_adjacency(polygons, &[(0, 1), (1, 2)]).unwrap();
+        assert_eq!((grid.width, grid.height), (3, 1));
+
+        let middle = grid.cell_at(&Position { x: 1, y: 0 }).unwrap();
+        assert_eq!(
+            middle.neighbours.get(&Direction::Adjacent(1)),
+            Some(&Position { x: 2, y: 0 })
+        );
+        assert!(grid.cell_at(&Position { x: 3, y: 0 }).is_none());
+
+        grid.link(&Position { x: 0, y: 0 }, &Position { x: 1, y: 0 });
+        let svg = grid.draw(grid.map(), grid.width(), grid.height());
+        // open between the first two, closed between the last two
+        assert!(!svg.contains("<line x1=\"16.00\" y1=\"0.00\" x2=\"16.00\" y2=\"16.00\""));
+        assert!(svg.contains("<line x1=\"32.00\" y1=\"0.00\" x2=\"32.00\" y2=\"16.00\""));
+
+        assert_eq!(
+            GraphGrid::from_adjacency(vec![square(0.0, 0.0)], &[(0, 1)]),
+            Err(GraphError::UnknownCell(1))
+        );
+        assert_eq!(
+            GraphGrid::from_sides(vec![square(0.0, 0.0)], vec![vec![None]]),
+            Err(GraphError::MissingSides(0))
+        );
+    }
+
+    #[test]
+    fn should_generate_voronoi_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let grid = GraphGrid::voronoi(6, 4, 30, &mut rng);
+        assert_eq!(grid.map.len(), 30);
+
+        // neighbours go both ways
+        for cell in &grid.map {
+            for neighbour in cell.get_neighbours() {
+                let other = grid.cell_at(&neighbour).unwrap();
+                assert!(other.neighbours.values().any(|pos| *pos == cell.position));
+            }
+        }
+
+        let mut grid: Box<dyn Grid> = Box::new(grid);
+        recursive_backtracker(&mut grid, &mut rng);
+        let reachable = distances(&Position { x: 0, y: 0 }, grid.links());
+        assert_eq!(reachable.len(), 30);
+
+        let svg = grid.draw(grid.map(), grid.width(), grid.height());
+        assert!(svg.starts_with("<svg viewBox=\"0 0 96 64\""));
+
+        // every side between linked cells is open, and every outside side
+        // is drawn
+        let line = |a: (f64, f64), b: (f64, f64)| {
+            format!(
+                "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\"",
+                a.0 * 16.0,
+                a.1 * 16.0,
+                b.0 * 16.0,
+                b.1 * 16.0
+            )
+        };
+        let mut open = 0;
+        for cell in grid.map() {
+            let polygon = grid.polygon(&cell.position).unwrap();
+            let sides = grid.polygon_sides(&cell.position).unwrap();
+            for (i, across) in sides.iter().enumerate() {
+                let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+                match across {
+                    Some(other) if grid.is_linked(&cell.position, other) => {
+                        assert!(!svg.contains(&line(a, b)) && !svg.contains(&line(b, a)));
+                        open += 1;
+                    }
+                    Some(_) => {}
+                    None => assert!(svg.contains(&line(a, b))),
+                }
+            }
+        }
+        // 29 passages, usually seen from both sides
+        assert!(open >= 29);
+    }
+}
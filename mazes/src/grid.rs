@@ -3,12 +3,13 @@ use std::{
     fmt::{self, Display, Formatter},
 };
 
-use rand::Rng;
+use rand::seq::IndexedRandom;
 
 use crate::{
-    Position,
+    Direction, Position, Shape, Topology, algos,
     base_grid::{GridSetup, Svg},
     cell::Cell,
+    distances::Distances,
 };
 
 #[derive(Debug, Eq, PartialEq)]
@@ -17,13 +18,21 @@ pub struct StandardGrid {
     pub width: i32,
     pub height: i32,
     pub links: HashMap<Position, Vec<Position>>,
-    pub distances: HashMap<Position, i32>,
+    pub distances: Option<Distances>,
+    pub topology: Topology,
 }
 
 impl StandardGrid {
     pub fn new(width: i32, height: i32) -> Self {
+        Self::with_topology(width, height, Topology::Plane)
+    }
+
+    // a grid whose edges join up. generators that only ever carve south or
+    // east (binary, sidewinder, ellers) would leave loops on wrapped edges,
+    // so the registry doesn't offer them
+    pub fn with_topology(width: i32, height: i32, topology: Topology) -> Self {
         let mut map = Self::prepare_map(width, height);
-        Self::configure_cells(&mut map, width, height);
+        Self::configure_topology(&mut map, width, height, topology);
         let links = HashMap::new();
 
         StandardGrid {
@@ -31,7 +40,8 @@ impl StandardGrid {
             width,
             height,
             links,
-            distances: HashMap::new(),
+            distances: None,
+            topology,
         }
     }
 }
@@ -55,12 +65,16 @@ impl Grid for StandardGrid {
     fn set_links(&mut self, links: HashMap<Position, Vec<Position>>) {
         self.links = links;
     }
-    fn distances(&self) -> HashMap<Position, i32> {
-        self.distances.clone()
+    fn distances(&self) -> Option<&Distances> {
+        self.distances.as_ref()
+    }
+
+    fn set_distances(&mut self, distances: Distances) {
+        self.distances = Some(distances);
     }
 
-    fn set_distances(&mut self, distances: HashMap<Position, i32>) {
-        self.distances = distances;
+    fn topology(&self) -> Topology {
+        self.topology
     }
 }
 
@@ -71,8 +85,26 @@ pub trait Grid {
     fn links(&self) -> &HashMap<Position, Vec<Position>>;
     fn links_mut(&mut self) -> &mut HashMap<Position, Vec<Position>>;
     fn set_links(&mut self, links: HashMap<Position, Vec<Position>>);
-    fn distances(&self) -> HashMap<Position, i32>;
-    fn set_distances(&mut self, distances: HashMap<Position, i32>);
+    fn distances(&self) -> Option<&Distances>;
+    fn set_distances(&mut self, distances: Distances);
+
+    fn shape(&self) -> Shape {
+        Shape::Square
+    }
+
+    fn topology(&self) -> Topology {
+        Topology::Plane
+    }
+
+    // floors stacked in the map, each `height` rows tall
+    fn levels(&self) -> i32 {
+        1
+    }
+
+    // some cells of the width x height rectangle are left out of the map
+    fn masked(&self) -> bool {
+        false
+    }
 
     fn contents_of(&self, _cell: &Cell) -> String {
         String::from(" ")
@@ -80,21 +112,48 @@ pub trait Grid {
 
     fn random_cell(&self) -> Cell {
         let mut rng = rand::rng();
-        let y = rng.random_range(0..self.height());
-        let x = rng.random_range(0..self.width());
-
-        self.map()[((y * self.width()) + x) as usize].clone()
+        self.map()
+            .choose(&mut rng)
+            .expect("Grid has no cells")
+            .clone()
     }
 
-    fn cell_at(&mut self, pos: &Position) -> Option<&Cell> {
+    // cells are usually stored in row order, so check the expected index
+    // first before falling back to searching the whole map. nothing assumes
+    // every cell is inside width x height, so grids laid out some other way
+    // still work (if slowly) without overriding this
+    fn cell_at(&self, pos: &Position) -> Option<&Cell> {
+        if pos.x >= 0 && pos.y >= 0 && pos.x < self.width() && pos.y < self.height() {
+            let index = ((pos.y * self.width()) + pos.x) as usize;
+            if let Some(cell) = self.map().get(index)
+                && cell.position == *pos
+            {
+                return Some(cell);
+            }
+        }
         self.map().iter().find(|cell| cell.position == *pos)
     }
 
+    // corners of a cell, in cells rather than pixels, for grids whose cells
+    // aren't a regular shape
+    fn polygon(&self, _pos: &Position) -> Option<&[(f64, f64)]> {
+        None
+    }
+
+    // for each side of the polygon, from one corner to the next, the cell
+    // across it. None for sides on the outside
+    fn polygon_sides(&self, _pos: &Position) -> Option<&[Option<Position>]> {
+        None
+    }
+
+    // adds the passage in both directions
     fn link(&mut self, start: &Position, neighbour: &Position) {
-        self.links_mut()
-            .entry(*start)
-            .or_insert(vec![])
-            .push(*neighbour);
+        algos::link(self.links_mut(), start, neighbour);
+    }
+
+    // removes the passage in both directions
+    fn unlink(&mut self, start: &Position, neighbour: &Position) {
+        algos::unlink(self.links_mut(), start, neighbour);
     }
 
     fn is_linked(&self, start: &Position, pos: &Position) -> bool {
@@ -105,47 +164,74 @@ pub trait Grid {
     }
 
     fn size(&self) -> i32 {
-        self.width() * self.height()
+        self.map().len() as i32
     }
 }
 
+// cells missing from the map (eg masked out) are left blank,
+// with walls only where they border a cell that's there.
+// passages are read from each cell's own neighbours, so links across
+// joined edges show as gaps in the outside wall.
+// multi-level grids print each level in turn, separated by a blank line
 impl Display for dyn Grid {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let mut output: String = "+".to_owned();
-        for _ in 0..self.width() {
-            output += "---+"
-        }
-        output += "\n";
-
-        for y in 0..self.height() {
-            let mut left = "|".to_owned();
-            let mut bottom = "+".to_owned();
+        let linked = |cell: &Cell, direction| {
+            cell.neighbours
+                .get(&direction)
+                .is_some_and(|pos| self.is_linked(&cell.position, pos))
+        };
+        let mut output = String::new();
+
+        for level in 0..self.levels() {
+            if level > 0 {
+                output += "\n";
+            }
+            let top = level * self.height();
+            let bottom_row = top + self.height() - 1;
 
+            output += "+";
             for x in 0..self.width() {
-                let index = ((y * self.width()) + x) as usize;
-                let cell = self.map()[index].clone();
-                let body = self.contents_of(&cell);
-                left += &format!(" {} ", body);
-                if let Some(cell_links) = self.links().get(&Position { x, y }) {
-                    // east
-                    if cell_links.contains(&Position { x: x + 1, y }) {
-                        left += " ";
-                    } else {
-                        left += "|";
-                    }
+                match self.cell_at(&Position { x, y: top }) {
+                    Some(cell) if !linked(cell, Direction::North) => output += "---+",
+                    _ => output += "   +",
+                }
+            }
+            output += "\n";
+
+            for y in top..=bottom_row {
+                let mut left = match self.cell_at(&Position { x: 0, y }) {
+                    Some(cell) if !linked(cell, Direction::West) => "|".to_owned(),
+                    _ => " ".to_owned(),
+                };
+                let mut bottom = "+".to_owned();
+
+                for x in 0..self.width() {
+                    let pos = Position { x, y };
+                    let cell = self.cell_at(&pos);
+                    let body = match cell {
+                        Some(cell) => self.contents_of(cell),
+                        None => String::from(" "),
+                    };
+                    left += &format!(" {} ", body);
 
-                    // south
-                    if cell_links.contains(&Position { x, y: y + 1 }) {
-                        bottom += "   +";
-                    } else {
-                        bottom += "---+"
-                    }
-                } else {
-                    left += "|";
-                    bottom += "---+"
+                    // east
+                    let east_open = match cell {
+                        Some(cell) => linked(cell, Direction::East),
+                        None => self.cell_at(&Position { x: x + 1, y }).is_none(),
+                    };
+                    left += if east_open { " " } else { "|" };
+
+                    // south. the last row of a level has no south neighbours
+                    let south_open = match cell {
+                        Some(cell) => linked(cell, Direction::South),
+                        None => {
+                            y == bottom_row || self.cell_at(&Position { x, y: y + 1 }).is_none()
+                        }
+                    };
+                    bottom += if south_open { "   +" } else { "---+" };
                 }
+                output = output + &format!("{}\n{}\n", &left, &bottom).to_owned();
             }
-            output = output + &format!("{}\n{}\n", &left, &bottom).to_owned();
         }
         write!(f, "{}", output)
     }
@@ -160,7 +246,7 @@ mod test {
     use std::collections::HashMap;
 
     use crate::{
-        Direction, Position,
+        Direction, Position, Topology,
         cell::Cell,
         grid::{Grid, StandardGrid, Svg},
     };
@@ -174,7 +260,8 @@ mod test {
                 links: HashMap::new(),
                 width: 4,
                 height: 4,
-                distances: HashMap::new(),
+                distances: None,
+                topology: Topology::Plane,
                 map: vec![
                     Cell {
                         position: Position { x: 0, y: 0 },
@@ -343,6 +430,101 @@ mod test {
         assert_eq!(links, container.links);
     }
 
+    #[test]
+    fn it_should_unlink_cells() {
+        let mut grid: Box<dyn Grid> = Box::new(StandardGrid::new(2, 1));
+        let a = Position { x: 0, y: 0 };
+        let b = Position { x: 1, y: 0 };
+        grid.link(&a, &b);
+        assert!(grid.is_linked(&b, &a));
+
+        grid.unlink(&b, &a);
+        assert!(!grid.is_linked(&a, &b));
+        assert!(!grid.is_linked(&b, &a));
+    }
+
+    #[test]
+    fn it_should_wrap_edges() {
+        let corner = Position { x: 0, y: 0 };
+
+        // two cells across, so east and west are the same cell
+        let narrow = StandardGrid::with_topology(2, 2, Topology::Torus);
+        let cell = narrow.cell_at(&corner).unwrap();
+        assert_eq!(
+            cell.get_neighbours(),
+            vec![Position { x: 1, y: 0 }, Position { x: 0, y: 1 }]
+        );
+        assert_eq!(
+            cell.neighbours.get(&Direction::East),
+            Some(&Position { x: 1, y: 0 })
+        );
+
+        let cylinder = StandardGrid::with_topology(4, 3, Topology::Cylinder);
+        let cell = cylinder.cell_at(&corner).unwrap();
+        assert_eq!(
+            cell.neighbours.get(&Direction::West),
+            Some(&Position { x: 3, y: 0 })
+        );
+        assert_eq!(cell.neighbours.get(&Direction::North), None);
+
+        let mut torus: Box<dyn Grid> = Box::new(StandardGrid::with_topology(4, 3, Topology::Torus));
+        let cell = torus.cell_at(&corner).unwrap();
+        assert_eq!(
+            cell.neighbours.get(&Direction::North),
+            Some(&Position { x: 0, y: 2 })
+        );
+
+        // linked across the edge, so the outside wall has a gap with stubs
+        let west = Position { x: 3, y: 0 };
+        torus.link(&corner, &west);
+        let svg = torus.draw(torus.map(), torus.width(), torus.height());
+        assert!(svg.starts_with("<svg viewBox=\"-4 -4 72 56\""));
+        assert!(svg.contains(
+            "<line x1=\"0\" y1=\"0\" x2=\"-4\" y2=\"0\" stroke=\"black\" stroke-linecap=\"square\" />"
+        ));
+        assert!(!svg.contains(
+            "<line x1=\"0\" y1=\"0\" x2=\"0\" y2=\"16\" stroke=\"black\" stroke-linecap=\"square\" />"
+        ));
+    }
+
+    #[test]
+    fn it_should_flip_across_twisted_edges() {
+        let mobius = StandardGrid::with_topology(3, 3, Topology::Mobius);
+        let cell = mobius.cell_at(&Position { x: 2, y: 0 }).unwrap();
+        assert_eq!(
+            cell.neighbours.get(&Direction::East),
+            Some(&Position { x: 0, y: 2 })
+        );
+        assert_eq!(cell.neighbours.get(&Direction::North), None);
+
+        let mut klein: Box<dyn Grid> = Box::new(StandardGrid::with_topology(3, 3, Topology::Klein));
+        let corner = Position { x: 0, y: 0 };
+        let cell = klein.cell_at(&corner).unwrap();
+        assert_eq!(
+            cell.neighbours.get(&Direction::West),
+            Some(&Position { x: 2, y: 2 })
+        );
+        assert_eq!(
+            cell.neighbours.get(&Direction::North),
+            Some(&Position { x: 0, y: 2 })
+        );
+
+        // gaps in the outside wall where the links leave
+        let opposite = Position { x: 2, y: 2 };
+        klein.link(&corner, &opposite);
+        assert_eq!(
+            format!("{}", klein),
+            "+---+---+---+
+    |   |   |
++---+---+---+
+|   |   |   |
++---+---+---+
+|   |   |    
++---+---+---+
+"
+        );
+    }
+
     #[test]
     fn it_should_display_ascii() {
         let mut grid: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
@@ -395,7 +577,7 @@ mod test {
     #[test]
     fn it_should_draw_svg() {
         let grid: Box<dyn Grid> = Box::new(StandardGrid::new(4, 4));
-        let svg = grid.draw(&grid.map(), grid.width(), grid.height());
+        let svg = grid.draw(grid.map(), grid.width(), grid.height());
         assert_eq!(
             "<svg viewBox=\"0 0 64 64\" xmlns=\"http://www.w3.org/2000/svg\"><line x1=\"0\" y1=\"0\" x2=\"16\" y2=\"0\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"0\" y1=\"0\" x2=\"0\" y2=\"16\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"16\" y1=\"0\" x2=\"16\" y2=\"16\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"0\" y1=\"16\" x2=\"16\" y2=\"16\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"16\" y1=\"0\" x2=\"32\" y2=\"0\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"32\" y1=\"0\" x2=\"32\" y2=\"16\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"16\" y1=\"16\" x2=\"32\" y2=\"16\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"32\" y1=\"0\" x2=\"48\" y2=\"0\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"48\" y1=\"0\" x2=\"48\" y2=\"16\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"32\" y1=\"16\" x2=\"48\" y2=\"16\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"48\" y1=\"0\" x2=\"64\" y2=\"0\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"64\" y1=\"0\" x2=\"64\" y2=\"16\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"48\" y1=\"16\" x2=\"64\" y2=\"16\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"0\" y1=\"16\" x2=\"0\" y2=\"32\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"16\" y1=\"16\" x2=\"16\" y2=\"32\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"0\" y1=\"32\" x2=\"16\" y2=\"32\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"32\" y1=\"16\" x2=\"32\" y2=\"32\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"16\" y1=\"32\" x2=\"32\" y2=\"32\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"48\" y1=\"16\" x2=\"48\" y2=\"32\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"32\" y1=\"32\" x2=\"48\" y2=\"32\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"64\" y1=\"16\" x2=\"64\" y2=\"32\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"48\" y1=\"32\" x2=\"64\" y2=\"32\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"0\" y1=\"32\" x2=\"0\" y2=\"48\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"16\" y1=\"32\" x2=\"16\" y2=\"48\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"0\" y1=\"48\" x2=\"16\" y2=\"48\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"32\" y1=\"32\" x2=\"32\" y2=\"48\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"16\" y1=\"48\" x2=\"32\" y2=\"48\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"48\" y1=\"32\" x2=\"48\" y2=\"48\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"32\" y1=\"48\" x2=\"48\" y2=\"48\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"64\" y1=\"32\" x2=\"64\" y2=\"48\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"48\" y1=\"48\" x2=\"64\" y2=\"48\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"0\" y1=\"48\" x2=\"0\" y2=\"64\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"16\" y1=\"48\" x2=\"16\" y2=\"64\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"0\" y1=\"64\" x2=\"16\" y2=\"64\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"32\" y1=\"48\" x2=\"32\" y2=\"64\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"16\" y1=\"64\" x2=\"32\" y2=\"64\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"48\" y1=\"48\" x2=\"48\" y2=\"64\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"32\" y1=\"64\" x2=\"48\" y2=\"64\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"64\" y1=\"48\" x2=\"64\" y2=\"64\" stroke=\"black\" stroke-linecap=\"square\" /><line x1=\"48\" y1=\"64\" x2=\"64\" y2=\"64\" stroke=\"black\" stroke-linecap=\"square\" /></svg>",
             svg
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+use crate::{
+    Direction, Position, Position3D, Shape,
+    base_grid::{GridSetup, Svg},
+    cell::Cell,
+    distances::Distances,
+    grid::Grid,
+};
+
+// several square levels of the same size, joined by stairs between cells
+// directly above and below each other. the levels are stacked in one map
+// (see `Position3D`), so generators only see more cells with more neighbours
+#[derive(Debug, Eq, PartialEq)]
+pub struct Grid3D {
+    pub map: Vec<Cell>,
+    pub width: i32,
+    pub height: i32,
+    pub levels: i32,
+    pub links: HashMap<Position, Vec<Position>>,
+    pub distances: Option<Distances>,
+}
+
+impl Grid3D {
+    pub fn new(width: i32, height: i32, levels: i32) -> Self {
+        let mut map = Self::prepare_map(width, height * levels);
+        Self::configure_levels(&mut map, width, height, levels);
+
+        Grid3D {
+            map,
+            width,
+            height,
+            levels,
+            links: HashMap::new(),
+            distances: None,
+        }
+    }
+
+    pub fn cell_at_3d(&self, pos: &Position3D) -> Option<&Cell> {
+        if pos.y < 0 || pos.y >= self.height {
+            return None;
+        }
+        self.cell_at(&pos.to_position(self.height))
+    }
+
+    // north and south stay on the same level, up and down are the
+    // same square on the next level
+    fn configure_levels(map: &mut [Cell], width: i32, height: i32, levels: i32) {
+        for cell in map.iter_mut() {
+            let pos = Position3D::from_position(&cell.position, height);
+            let neighbours = [
+                (
+                    Direction::North,
+                    pos.y > 0,
+                    Position3D {
+                        y: pos.y - 1,
+                        ..pos
+                    },
+                ),
+                (
+                    Direction::South,
+                    pos.y < height - 1,
+                    Position3D {
+                        y: pos.y + 1,
+                        ..pos
+                    },
+                ),
+                (
+                    Direction::West,
+                    pos.x > 0,
+                    Position3D {
+                        x: pos.x - 1,
+                        ..pos
+                    },
+                ),
+                (
+                    Direction::East,
+                    pos.x < width - 1,
+                    Position3D {
+                        x: pos.x + 1,
+                        ..pos
+                    },
+                ),
+                (
+                    Direction::Up,
+                    pos.level > 0,
+                    Position3D {
+                        level: pos.level - 1,
+                        ..pos
+                    },
+                ),
+                (
+                    Direction::Down,
+                    pos.level < levels - 1,
+                    Position3D {
+                        level: pos.level + 1,
+                        ..pos
+                    },
+                ),
+            ];
+
+            for (direction, in_grid, neighbour) in neighbours {
+                if in_grid {
+                    cell.neighbours
+                        .insert(direction, neighbour.to_position(height));
+                }
+            }
+        }
+    }
+}
+
+impl Grid for Grid3D {
+    fn map(&self) -> &Vec<Cell> {
+        &self.map
+    }
+    fn width(&self) -> i32 {
+        self.width
+    }
+    // rows in each level, not the whole map. generators that walk the grid
+    // row by row never add stairs, so the floors stay cut off from each
+    // other: binary and sidewinder carve every level on its own, and ellers
+    // and recursive-division stop after the first `height` rows. the
+    // registry doesn't offer any of them for levels
+    fn height(&self) -> i32 {
+        self.height
+    }
+    fn links(&self) -> &HashMap<Position, Vec<Position>> {
+        &self.links
+    }
+    fn links_mut(&mut self) -> &mut HashMap<Position, Vec<Position>> {
+        &mut self.links
+    }
+    fn set_links(&mut self, links: HashMap<Position, Vec<Position>>) {
+        self.links = links;
+    }
+    fn distances(&self) -> Option<&Distances> {
+        self.distances.as_ref()
+    }
+
+    fn set_distances(&mut self, distances: Distances) {
+        self.distances = Some(distances);
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::Levels
+    }
+
+    fn levels(&self) -> i32 {
+        self.levels
+    }
+
+    // `^` for stairs up, `v` for down and `x` for both
+    fn contents_of(&self, cell: &Cell) -> String {
+        let stairs = |direction| {
+            cell.neighbours
+                .get(&direction)
+                .is_some_and(|pos| self.is_linked(&cell.position, pos))
+        };
+        match (stairs(Direction::Up), stairs(Direction::Down)) {
+            (true, true) => String::from("x"),
+            (true, false) => String::from("^"),
+            (false, true) => String::from("v"),
+            (false, false) => String::from(" "),
+        }
+    }
+
+    fn cell_at(&self, pos: &Position) -> Option<&Cell> {
+        if pos.x < 0 || pos.y < 0 || pos.x >= self.width || pos.y >= self.height * self.levels {
+            return None;
+        }
+        self.map.get(((pos.y * self.width) + pos.x) as usize)
+    }
+}
+
+impl GridSetup for Grid3D {}
+
+impl Svg for Grid3D {}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        Direction, Position, Position3D,
+        algos::{GENERATORS, recursive_backtracker::recursive_backtracker},
+        base_grid::Svg,
+        distances::distances,
+        grid::Grid,
+    };
+
+    use super::Grid3D;
+
+    #[test]
+    fn it_should_join_levels() {
+        let grid = Grid3D::new(3, 2, 3);
+        assert_eq!(grid.map.len(), 18);
+
+        let pos = Position3D {
+            x: 1,
+            y: 1,
+            level: 1,
+        };
+        assert_eq!(pos.to_position(2), Position { x: 1, y: 3 });
+        assert_eq!(Position3D::from_position(&Position { x: 1, y: 3 }, 2), pos);
+
+        let cell = grid.cell_at_3d(&pos).unwrap();
+        assert_eq!(
+            cell.neighbours.get(&Direction::Up),
+            Some(&Position { x: 1, y: 1 })
+        );
+        assert_eq!(
+            cell.neighbours.get(&Direction::Down),
+            Some(&Position { x: 1, y: 5 })
+        );
+        // the bottom row of a level doesn't lead onto the next one
+        assert_eq!(cell.neighbours.get(&Direction::South), None);
+        assert!(
+            grid.cell_at_3d(&Position3D {
+                x: 1,
+                y: 2,
+                level: 0
+            })
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn should_generate_3d_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut grid: Box<dyn Grid> = Box::new(Grid3D::new(3, 3, 2));
+        recursive_backtracker(&mut grid, &mut rng);
+
+        let reachable = distances(&Position { x: 0, y: 0 }, grid.links());
+        assert_eq!(reachable.len(), 18);
+
+        assert_eq!(
+            format!("{}", grid),
+            "+---+---+---+
+|     v     |
++---+---+---+
+| v         |
++---+---+   +
+| v | v     |
++---+---+---+
+
++---+---+---+
+|   | ^     |
++   +---+   +
+| ^ |       |
++---+   +   +
+| ^   ^ |   |
++---+---+---+
+"
+        );
+
+        let svg = grid.draw(grid.map(), grid.width(), grid.height());
+        assert!(svg.starts_with("<svg viewBox=\"0 0 112 48\""));
+    }
+
+    #[test]
+    fn every_supported_generator_should_reach_every_level() {
+        let seed = "abc12345abc";
+        let mut unsupported = vec![];
+
+        for generator in GENERATORS {
+            let mut grid: Box<dyn Grid> = Box::new(Grid3D::new(4, 3, 3));
+            if !generator.supports(grid.as_ref()) {
+                unsupported.push(generator.name);
+                continue;
+            }
+
+            let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+            (generator.generate)(&mut grid, &mut rng);
+
+            let reachable = distances(&Position { x: 0, y: 0 }, grid.links());
+            assert_eq!(reachable.len(), 36, "{}", generator.name);
+        }
+
+        // these never add stairs between the floors
+        assert_eq!(
+            unsupported,
+            vec!["binary", "sidewinder", "ellers", "recursive-division"]
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use crate::{
+    Direction, Position, Shape,
+    base_grid::{GridSetup, Svg},
+    cell::Cell,
+    distances::Distances,
+    grid::Grid,
+};
+
+// flat-topped hexagons with six neighbours each. cells are in columns,
+// with every odd column shifted half a cell down
+#[derive(Debug, Eq, PartialEq)]
+pub struct HexGrid {
+    pub map: Vec<Cell>,
+    pub width: i32,
+    pub height: i32,
+    pub links: HashMap<Position, Vec<Position>>,
+    pub distances: Option<Distances>,
+}
+
+impl HexGrid {
+    pub fn new(width: i32, height: i32) -> Self {
+        let mut map = Self::prepare_map(width, height);
+        Self::configure_cells(&mut map, width, height);
+
+        HexGrid {
+            map,
+            width,
+            height,
+            links: HashMap::new(),
+            distances: None,
+        }
+    }
+}
+
+impl Grid for HexGrid {
+    fn map(&self) -> &Vec<Cell> {
+        &self.map
+    }
+    fn width(&self) -> i32 {
+        self.width
+    }
+    fn height(&self) -> i32 {
+        self.height
+    }
+    fn links(&self) -> &HashMap<Position, Vec<Position>> {
+        &self.links
+    }
+    fn links_mut(&mut self) -> &mut HashMap<Position, Vec<Position>> {
+        &mut self.links
+    }
+    fn set_links(&mut self, links: HashMap<Position, Vec<Position>>) {
+        self.links = links;
+    }
+    fn distances(&self) -> Option<&Distances> {
+        self.distances.as_ref()
+    }
+
+    fn set_distances(&mut self, distances: Distances) {
+        self.distances = Some(distances);
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::Hex
+    }
+}
+
+impl GridSetup for HexGrid {
+    fn configure_cells(map: &mut Vec<Cell>, width: i32, height: i32) {
+        let in_grid = |pos: &Position| pos.x >= 0 && pos.y >= 0 && pos.x < width && pos.y < height;
+
+        for cell in map.iter_mut() {
+            let row = cell.position.y;
+            let col = cell.position.x;
+
+            // diagonal neighbours are a row up in even columns,
+            // and a row down in odd ones
+            let (north_diagonal, south_diagonal) = if col % 2 == 0 {
+                (row - 1, row)
+            } else {
+                (row, row + 1)
+            };
+
+            let neighbours = [
+                (Direction::North, Position { x: col, y: row - 1 }),
+                (Direction::South, Position { x: col, y: row + 1 }),
+                (
+                    Direction::NorthWest,
+                    Position {
+                        x: col - 1,
+                        y: north_diagonal,
+                    },
+                ),
+                (
+                    Direction::NorthEast,
+                    Position {
+                        x: col + 1,
+                        y: north_diagonal,
+                    },
+                ),
+                (
+                    Direction::SouthWest,
+                    Position {
+                        x: col - 1,
+                        y: south_diagonal,
+                    },
+                ),
+                (
+                    Direction::SouthEast,
+                    Position {
+                        x: col + 1,
+                        y: south_diagonal,
+                    },
+                ),
+            ];
+
+            for (direction, pos) in neighbours {
+                if in_grid(&pos) {
+                    cell.neighbours.insert(direction, pos);
+                }
+            }
+        }
+    }
+}
+
+impl Svg for HexGrid {}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        Direction, Position,
+        algos::recursive_backtracker::recursive_backtracker,
+        base_grid::{CELL_SIZE, Svg, joins, removed_walls, svg_walls},
+        distances::distances,
+        grid::Grid,
+    };
+
+    use super::HexGrid;
+
+    #[test]
+    fn it_should_find_six_neighbours() {
+        let grid = HexGrid::new(4, 4);
+
+        let even = grid.cell_at(&Position { x: 2, y: 1 }).unwrap();
+        assert_eq!(even.neighbours.len(), 6);
+        assert_eq!(
+            even.neighbours.get(&Direction::NorthEast),
+            Some(&Position { x: 3, y: 0 })
+        );
+        assert_eq!(
+            even.neighbours.get(&Direction::SouthWest),
+            Some(&Position { x: 1, y: 1 })
+        );
+
+        let odd = grid.cell_at(&Position { x: 1, y: 1 }).unwrap();
+        assert_eq!(
+            odd.neighbours.get(&Direction::NorthWest),
+            Some(&Position { x: 0, y: 1 })
+        );
+        assert_eq!(
+            odd.neighbours.get(&Direction::SouthEast),
+            Some(&Position { x: 2, y: 2 })
+        );
+
+        let corner = grid.cell_at(&Position { x: 0, y: 0 }).unwrap();
+        assert_eq!(corner.neighbours.len(), 2);
+    }
+
+    #[test]
+    fn should_generate_hex_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut grid: Box<dyn Grid> = Box::new(HexGrid::new(5, 5));
+        recursive_backtracker(&mut grid, &mut rng);
+
+        let reachable = distances(&Position { x: 0, y: 0 }, grid.links());
+        assert_eq!(reachable.len(), 25);
+
+        let svg = grid.draw(grid.map(), grid.width(), grid.height());
+        assert!(svg.starts_with("<svg viewBox=\"0 0 128.00 152.42\""));
+    }
+
+    #[test]
+    fn it_should_open_linked_sides() {
+        let mut grid: Box<dyn Grid> = Box::new(HexGrid::new(2, 2));
+        let closed = grid.draw(grid.map(), grid.width(), grid.height());
+        // four hexagons, sharing five sides between them
+        assert_eq!(svg_walls(&closed).len(), 4 * 6 - 5);
+
+        // the top left cell's south east side, running from its far east
+        // corner down to its near east corner on the bottom
+        grid.link(&Position { x: 0, y: 0 }, &Position { x: 1, y: 0 });
+        let open = grid.draw(grid.map(), grid.width(), grid.height());
+        let removed = removed_walls(&closed, &open);
+
+        let size = CELL_SIZE as f64;
+        let b_size = size * 3.0_f64.sqrt() / 2.0;
+        let (cx, cy) = (size, b_size);
+        assert_eq!(removed.len(), 1);
+        assert!(joins(
+            &removed[0],
+            (cx + size, cy),
+            (cx + size / 2.0, cy + b_size)
+        ));
+    }
+}
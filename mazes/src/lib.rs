@@ -8,11 +8,23 @@
 // use rand_seeder::Seeder;
 
 pub mod algos;
+pub mod astar;
 pub mod base_grid;
 mod cell;
+pub mod disjoint_set;
 pub mod distances;
 pub mod djikstra;
+pub mod graph_grid;
 pub mod grid;
+pub mod grid_3d;
+pub mod hex_grid;
+pub mod mask;
+pub mod masked_grid;
+pub mod polar_grid;
+pub mod triangle_grid;
+pub mod upsilon_grid;
+pub mod weave_grid;
+pub mod weights;
 
 #[derive(Debug, Eq, Hash, PartialEq, Clone, Copy)]
 pub struct Position {
@@ -20,10 +32,123 @@ pub struct Position {
     pub y: i32,
 }
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+// a cell on one floor of a multi-level grid. levels are stored one under
+// another in a single map, so each converts to a plain position with the
+// rows of the levels above added on
+#[derive(Debug, Eq, Hash, PartialEq, Clone, Copy)]
+pub struct Position3D {
+    pub x: i32,
+    pub y: i32,
+    pub level: i32,
+}
+
+impl Position3D {
+    // `height` is the number of rows in each level
+    pub fn to_position(&self, height: i32) -> Position {
+        Position {
+            x: self.x,
+            y: self.level * height + self.y,
+        }
+    }
+
+    pub fn from_position(pos: &Position, height: i32) -> Self {
+        Position3D {
+            x: pos.x,
+            y: pos.y.rem_euclid(height),
+            level: pos.y.div_euclid(height),
+        }
+    }
+}
+
+// grids only use the directions that suit their shape, and new shapes may
+// need more, so matches outside the crate need a catch-all
+#[derive(Debug, Eq, PartialEq, Hash, Clone, PartialOrd, Ord)]
+#[non_exhaustive]
 pub enum Direction {
     North,
     East,
     South,
     West,
+    // polar grids
+    Inward,
+    // a cell can have more than one neighbour further out, numbered clockwise
+    Outward(u8),
+    Clockwise,
+    CounterClockwise,
+    // hex and upsilon grids
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+    // stairs between levels
+    Up,
+    Down,
+    // graph grids, numbered in the order the neighbours were found
+    Adjacent(usize),
+}
+
+// how the edges of a square grid join up
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy)]
+pub enum Topology {
+    // edges are the outside walls
+    #[default]
+    Plane,
+    // the east edge joins the west edge
+    Cylinder,
+    // east joins west and south joins north
+    Torus,
+    // the east edge joins the west edge upside down
+    Mobius,
+    // east joins west upside down, south joins north
+    Klein,
+}
+
+impl Topology {
+    // where a step to (x, y) lands, once it's wrapped around any joined
+    // edges. None if it goes off an edge that isn't joined to anything
+    pub fn wrap(&self, x: i32, y: i32, width: i32, height: i32) -> Option<Position> {
+        // wraps east-west, flips rows when it does, wraps north-south
+        let (wraps_x, flips, wraps_y) = match self {
+            Topology::Plane => (false, false, false),
+            Topology::Cylinder => (true, false, false),
+            Topology::Torus => (true, false, true),
+            Topology::Mobius => (true, true, false),
+            Topology::Klein => (true, true, true),
+        };
+
+        let crosses_x = x < 0 || x >= width;
+        let (x, y) = match (crosses_x, wraps_x) {
+            (false, _) => (x, y),
+            (true, false) => return None,
+            (true, true) if flips => (x.rem_euclid(width), height - 1 - y),
+            (true, true) => (x.rem_euclid(width), y),
+        };
+        let y = match wraps_y {
+            true => y.rem_euclid(height),
+            false if y < 0 || y >= height => return None,
+            false => y,
+        };
+        Some(Position { x, y })
+    }
+}
+
+// how cells are laid out, so renderers know what to draw
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Shape {
+    Square,
+    // rings of cells around a centre, with x as the index around the ring
+    // and y as the ring
+    Polar,
+    // flat-topped hexagons in columns, with odd columns shifted half a cell down
+    Hex,
+    // alternating upright and inverted triangles, upright where x + y is even
+    Triangle,
+    // square cells where one passage can tunnel under another
+    Weave,
+    // square levels joined by stairs, stored one level under another
+    Levels,
+    // octagons where x + y is even, with small squares in the gaps between them
+    Upsilon,
+    // any polygons, with neighbours from a graph rather than a layout
+    Graph,
 }
@@ -0,0 +1,223 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    fs::{File, read_to_string},
+    io::{self, BufRead, BufReader, Seek},
+    path::Path,
+};
+
+use png::{ColorType, Decoder, DecodingError, Transformations};
+
+use crate::Position;
+
+// which cells of a width x height rectangle are switched on
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Mask {
+    pub width: i32,
+    pub height: i32,
+    bits: Vec<bool>,
+}
+
+#[derive(Debug)]
+pub enum MaskError {
+    Io(io::Error),
+    Png(DecodingError),
+    // no cells at all, or none switched on
+    Empty,
+}
+
+impl Display for MaskError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MaskError::Io(err) => write!(f, "couldn't read mask: {}", err),
+            MaskError::Png(err) => write!(f, "couldn't decode mask image: {}", err),
+            MaskError::Empty => write!(f, "mask has no cells switched on"),
+        }
+    }
+}
+
+impl Error for MaskError {}
+
+impl From<io::Error> for MaskError {
+    fn from(err: io::Error) -> Self {
+        MaskError::Io(err)
+    }
+}
+
+impl From<DecodingError> for MaskError {
+    fn from(err: DecodingError) -> Self {
+        MaskError::Png(err)
+    }
+}
+
+impl Mask {
+    // every cell on
+    pub fn new(width: i32, height: i32) -> Self {
+        Mask {
+            width,
+            height,
+            bits: vec![true; (width.max(0) * height.max(0)) as usize],
+        }
+    }
+
+    // one line per row, `X` for cells that are off and anything else for on.
+    // short lines are padded out with cells that are on
+    pub fn from_ascii(text: &str) -> Result<Self, MaskError> {
+        let lines: Vec<&str> = text
+            .trim_end()
+            .lines()
+            .map(|line| line.trim_end())
+            .collect();
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut mask = Mask::new(width as i32, lines.len() as i32);
+        for (y, line) in lines.iter().enumerate() {
+            for (x, ch) in line.chars().enumerate() {
+                if ch.eq_ignore_ascii_case(&'x') {
+                    mask.set(
+                        &Position {
+                            x: x as i32,
+                            y: y as i32,
+                        },
+                        false,
+                    );
+                }
+            }
+        }
+
+        mask.check()
+    }
+
+    pub fn from_txt(path: impl AsRef<Path>) -> Result<Self, MaskError> {
+        Self::from_ascii(&read_to_string(path)?)
+    }
+
+    // one cell per pixel, dark pixels are off
+    pub fn from_png_reader<R: BufRead + Seek>(reader: R) -> Result<Self, MaskError> {
+        let mut decoder = Decoder::new(reader);
+        decoder.set_transformations(Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size().ok_or(MaskError::Empty)?];
+        let info = reader.next_frame(&mut buffer)?;
+        let pixels = &buffer[..info.buffer_size()];
+
+        let channels = match info.color_type {
+            ColorType::Grayscale => 1,
+            ColorType::GrayscaleAlpha => 2,
+            ColorType::Rgb => 3,
+            ColorType::Rgba => 4,
+            // expanded to rgb by normalize_to_color8
+            ColorType::Indexed => 3,
+        };
+
+        let mut mask = Mask::new(info.width as i32, info.height as i32);
+        for y in 0..info.height as usize {
+            let row = &pixels[y * info.line_size..(y + 1) * info.line_size];
+            for x in 0..info.width as usize {
+                let pixel = &row[x * channels..(x + 1) * channels];
+                // alpha doesn't count towards brightness
+                let colours = if channels > 2 {
+                    &pixel[..3]
+                } else {
+                    &pixel[..1]
+                };
+                let brightness =
+                    colours.iter().map(|c| *c as u32).sum::<u32>() / colours.len() as u32;
+                if brightness < 128 {
+                    mask.set(
+                        &Position {
+                            x: x as i32,
+                            y: y as i32,
+                        },
+                        false,
+                    );
+                }
+            }
+        }
+
+        mask.check()
+    }
+
+    pub fn from_png(path: impl AsRef<Path>) -> Result<Self, MaskError> {
+        Self::from_png_reader(BufReader::new(File::open(path)?))
+    }
+
+    pub fn is_on(&self, pos: &Position) -> bool {
+        self.index(pos).is_some_and(|index| self.bits[index])
+    }
+
+    pub fn set(&mut self, pos: &Position, on: bool) {
+        if let Some(index) = self.index(pos) {
+            self.bits[index] = on;
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.bits.iter().filter(|on| **on).count()
+    }
+
+    fn index(&self, pos: &Position) -> Option<usize> {
+        if pos.x < 0 || pos.y < 0 || pos.x >= self.width || pos.y >= self.height {
+            return None;
+        }
+        Some(((pos.y * self.width) + pos.x) as usize)
+    }
+
+    fn check(self) -> Result<Self, MaskError> {
+        match self.count() {
+            0 => Err(MaskError::Empty),
+            _ => Ok(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use png::{BitDepth, ColorType, Encoder};
+
+    use crate::Position;
+
+    use super::{Mask, MaskError};
+
+    #[test]
+    fn it_should_read_ascii_masks() {
+        let mask = Mask::from_ascii("X..X\n....\n.X\n").unwrap();
+        assert_eq!(mask.width, 4);
+        assert_eq!(mask.height, 3);
+        assert_eq!(mask.count(), 9);
+        assert!(!mask.is_on(&Position { x: 0, y: 0 }));
+        assert!(!mask.is_on(&Position { x: 1, y: 2 }));
+        // padded
+        assert!(mask.is_on(&Position { x: 3, y: 2 }));
+        assert!(!mask.is_on(&Position { x: 4, y: 0 }));
+
+        assert!(matches!(Mask::from_ascii("XX\nXX"), Err(MaskError::Empty)));
+    }
+
+    #[test]
+    fn it_should_read_png_masks() {
+        // 3x2, black pixels in the corners
+        let pixels = [0, 255, 255, 255, 200, 0];
+        let mut image = vec![];
+        {
+            let mut encoder = Encoder::new(&mut image, 3, 2);
+            encoder.set_color(ColorType::Grayscale);
+            encoder.set_depth(BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            writer.write_image_data(&pixels).unwrap();
+        }
+
+        let mask = Mask::from_png_reader(Cursor::new(image)).unwrap();
+        assert_eq!((mask.width, mask.height), (3, 2));
+        assert_eq!(mask.count(), 4);
+        assert!(!mask.is_on(&Position { x: 0, y: 0 }));
+        assert!(!mask.is_on(&Position { x: 2, y: 1 }));
+        assert!(mask.is_on(&Position { x: 1, y: 1 }));
+    }
+}
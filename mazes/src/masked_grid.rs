@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use crate::{
+    Position,
+    base_grid::{GridSetup, Svg},
+    cell::Cell,
+    distances::Distances,
+    grid::Grid,
+    mask::Mask,
+};
+
+// a grid with some cells switched off. switched off cells aren't in the map
+// at all, so generators never visit them and nothing is drawn for them
+#[derive(Debug, Eq, PartialEq)]
+pub struct MaskedGrid {
+    pub map: Vec<Cell>,
+    pub width: i32,
+    pub height: i32,
+    pub links: HashMap<Position, Vec<Position>>,
+    pub distances: Option<Distances>,
+    pub mask: Mask,
+}
+
+impl MaskedGrid {
+    pub fn new(mask: Mask) -> Self {
+        let width = mask.width;
+        let height = mask.height;
+        let mut map = Self::prepare_map(width, height);
+        map.retain(|cell| mask.is_on(&cell.position));
+        Self::configure_cells(&mut map, width, height);
+
+        MaskedGrid {
+            map,
+            width,
+            height,
+            links: HashMap::new(),
+            distances: None,
+            mask,
+        }
+    }
+}
+
+impl Grid for MaskedGrid {
+    fn map(&self) -> &Vec<Cell> {
+        &self.map
+    }
+    fn width(&self) -> i32 {
+        self.width
+    }
+    fn height(&self) -> i32 {
+        self.height
+    }
+    fn links(&self) -> &HashMap<Position, Vec<Position>> {
+        &self.links
+    }
+    fn links_mut(&mut self) -> &mut HashMap<Position, Vec<Position>> {
+        &mut self.links
+    }
+    fn set_links(&mut self, links: HashMap<Position, Vec<Position>>) {
+        self.links = links;
+    }
+    fn distances(&self) -> Option<&Distances> {
+        self.distances.as_ref()
+    }
+
+    fn set_distances(&mut self, distances: Distances) {
+        self.distances = Some(distances);
+    }
+
+    fn masked(&self) -> bool {
+        self.map.len() < (self.width * self.height) as usize
+    }
+
+    // the map is still in row order, just with gaps
+    fn cell_at(&self, pos: &Position) -> Option<&Cell> {
+        self.map
+            .binary_search_by_key(&(pos.y, pos.x), |cell| (cell.position.y, cell.position.x))
+            .ok()
+            .map(|index| &self.map[index])
+    }
+}
+
+impl GridSetup for MaskedGrid {}
+
+impl Svg for MaskedGrid {}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        Direction, Position,
+        algos::{GENERATORS, recursive_backtracker::recursive_backtracker, wilsons::wilsons},
+        distances::distances,
+        grid::Grid,
+        mask::Mask,
+    };
+
+    use super::MaskedGrid;
+
+    #[test]
+    fn it_should_skip_disabled_cells() {
+        let grid = MaskedGrid::new(Mask::from_ascii("..\n.X").unwrap());
+        assert_eq!(grid.map.len(), 3);
+        assert_eq!(grid.size(), 3);
+        assert!(grid.cell_at(&Position { x: 1, y: 1 }).is_none());
+
+        let corner = grid.cell_at(&Position { x: 1, y: 0 }).unwrap();
+        assert_eq!(
+            corner.neighbours.get(&Direction::West),
+            Some(&Position { x: 0, y: 0 })
+        );
+        assert_eq!(corner.neighbours.get(&Direction::South), None);
+
+        assert!(grid.masked());
+        assert!(!MaskedGrid::new(Mask::new(2, 2)).masked());
+    }
+
+    #[test]
+    fn every_supported_generator_should_reach_every_cell() {
+        let seed = "abc12345abc";
+        let mask = Mask::from_ascii("X...XX\n..X...\n.XX.X.\n...X..\nX.....").unwrap();
+        let mut unsupported = vec![];
+
+        for generator in GENERATORS {
+            let mut grid: Box<dyn Grid> = Box::new(MaskedGrid::new(mask.clone()));
+            if !generator.supports(grid.as_ref()) {
+                unsupported.push(generator.name);
+                continue;
+            }
+
+            let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+            (generator.generate)(&mut grid, &mut rng);
+
+            // every cell reachable, with no loops
+            let link_count: usize = grid.links().values().map(|links| links.len()).sum();
+            assert_eq!(link_count, 2 * 20, "{}", generator.name);
+            let reachable = distances(&Position { x: 1, y: 0 }, grid.links());
+            assert_eq!(reachable.len(), 21, "{}", generator.name);
+        }
+
+        // these need the whole rectangle
+        assert_eq!(
+            unsupported,
+            vec!["binary", "sidewinder", "ellers", "recursive-division"]
+        );
+    }
+
+    #[test]
+    fn should_generate_masked_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mask = Mask::from_ascii("X....X\n......\n..XX..\n..XX..\n......\nX....X").unwrap();
+        let mut container: Box<dyn Grid> = Box::new(MaskedGrid::new(mask));
+        recursive_backtracker(&mut container, &mut rng);
+
+        assert_eq!(
+            format!("{}", container),
+            "+   +---+---+---+---+   +
+    |               |    
++---+   +---+   +---+---+
+|   |       |           |
++   +   +---+---+---+   +
+|       |       |       |
++---+---+   +   +   +   +
+|       |       |   |   |
++---+   +---+---+   +   +
+|       |           |   |
++---+   +---+   +   +---+
+    |           |   |    
++   +---+---+---+---+   +
+"
+        );
+    }
+
+    #[test]
+    fn should_finish_on_disconnected_masks() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        // two islands that can never be joined
+        let mask = Mask::from_ascii("..X..\n..X..").unwrap();
+        let mut container: Box<dyn Grid> = Box::new(MaskedGrid::new(mask));
+        wilsons(&mut container, &mut rng);
+
+        let link_count: usize = container.links().values().map(|links| links.len()).sum();
+        assert_eq!(link_count, 2 * 3);
+    }
+}
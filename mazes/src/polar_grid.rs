@@ -0,0 +1,232 @@
+use std::{collections::HashMap, f64::consts::PI};
+
+use crate::{
+    Direction, Position, Shape,
+    base_grid::{GridSetup, Svg},
+    cell::Cell,
+    distances::Distances,
+    grid::Grid,
+};
+
+// rings of cells around a single centre cell. rings further out are split
+// into more cells so the cells stay roughly square. positions are
+// { x: index around the ring (clockwise), y: ring }
+#[derive(Debug, Eq, PartialEq)]
+pub struct PolarGrid {
+    pub map: Vec<Cell>,
+    pub rings: i32,
+    pub links: HashMap<Position, Vec<Position>>,
+    pub distances: Option<Distances>,
+    // index in the map of the first cell of each ring
+    offsets: Vec<usize>,
+}
+
+impl PolarGrid {
+    pub fn new(rings: i32) -> Self {
+        let mut map = Self::prepare_map(0, rings);
+        Self::configure_cells(&mut map, 0, rings);
+
+        let mut offsets = vec![];
+        let mut offset = 0;
+        for count in Self::ring_counts(rings) {
+            offsets.push(offset);
+            offset += count as usize;
+        }
+
+        PolarGrid {
+            map,
+            rings,
+            links: HashMap::new(),
+            distances: None,
+            offsets,
+        }
+    }
+
+    // number of cells in each ring
+    pub fn ring_counts(rings: i32) -> Vec<i32> {
+        if rings <= 0 {
+            return vec![];
+        }
+
+        let row_height = 1.0 / rings as f64;
+        let mut counts = vec![1];
+
+        for ring in 1..rings {
+            let radius = ring as f64 / rings as f64;
+            let circumference = 2.0 * PI * radius;
+            let previous = counts[(ring - 1) as usize];
+            let cell_width = circumference / previous as f64;
+            let ratio = (cell_width / row_height).round() as i32;
+            counts.push(previous * ratio.max(1));
+        }
+
+        counts
+    }
+}
+
+impl Grid for PolarGrid {
+    fn map(&self) -> &Vec<Cell> {
+        &self.map
+    }
+    // cells in the outermost ring
+    fn width(&self) -> i32 {
+        Self::ring_counts(self.rings).last().copied().unwrap_or(0)
+    }
+    fn height(&self) -> i32 {
+        self.rings
+    }
+    fn links(&self) -> &HashMap<Position, Vec<Position>> {
+        &self.links
+    }
+    fn links_mut(&mut self) -> &mut HashMap<Position, Vec<Position>> {
+        &mut self.links
+    }
+    fn set_links(&mut self, links: HashMap<Position, Vec<Position>>) {
+        self.links = links;
+    }
+    fn distances(&self) -> Option<&Distances> {
+        self.distances.as_ref()
+    }
+
+    fn set_distances(&mut self, distances: Distances) {
+        self.distances = Some(distances);
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::Polar
+    }
+
+    fn cell_at(&self, pos: &Position) -> Option<&Cell> {
+        if pos.x < 0 || pos.y < 0 || pos.y >= self.rings {
+            return None;
+        }
+
+        let start = self.offsets[pos.y as usize];
+        let end = self
+            .offsets
+            .get(pos.y as usize + 1)
+            .copied()
+            .unwrap_or(self.map.len());
+        self.map[start..end].get(pos.x as usize)
+    }
+}
+
+impl GridSetup for PolarGrid {
+    // width is worked out per ring, height is the number of rings
+    fn prepare_map(_width: i32, height: i32) -> Vec<Cell> {
+        let mut map = vec![];
+        for (ring, count) in Self::ring_counts(height).into_iter().enumerate() {
+            for index in 0..count {
+                map.push(Cell::new(index, ring as i32));
+            }
+        }
+        map
+    }
+
+    fn configure_cells(map: &mut Vec<Cell>, _width: i32, height: i32) {
+        let counts = Self::ring_counts(height);
+
+        // outward neighbours are found from the inward side,
+        // so collect them first
+        let mut outward: HashMap<Position, Vec<Position>> = HashMap::new();
+
+        for cell in map.iter_mut() {
+            let ring = cell.position.y;
+            let index = cell.position.x;
+            if ring == 0 {
+                continue;
+            }
+
+            let count = counts[ring as usize];
+            cell.neighbours.insert(
+                Direction::Clockwise,
+                Position {
+                    x: (index + 1) % count,
+                    y: ring,
+                },
+            );
+            cell.neighbours.insert(
+                Direction::CounterClockwise,
+                Position {
+                    x: (index + count - 1) % count,
+                    y: ring,
+                },
+            );
+
+            let ratio = count / counts[(ring - 1) as usize];
+            let parent = Position {
+                x: index / ratio,
+                y: ring - 1,
+            };
+            cell.neighbours.insert(Direction::Inward, parent);
+            outward.entry(parent).or_default().push(cell.position);
+        }
+
+        for cell in map.iter_mut() {
+            if let Some(children) = outward.get(&cell.position) {
+                for (index, child) in children.iter().enumerate() {
+                    cell.neighbours
+                        .insert(Direction::Outward(index as u8), *child);
+                }
+            }
+        }
+    }
+}
+
+impl Svg for PolarGrid {}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        Direction, Position, algos::recursive_backtracker::recursive_backtracker, base_grid::Svg,
+        distances::distances, grid::Grid,
+    };
+
+    use super::PolarGrid;
+
+    #[test]
+    fn it_should_create_rings() {
+        assert_eq!(PolarGrid::ring_counts(4), vec![1, 6, 12, 24]);
+
+        let grid = PolarGrid::new(4);
+        assert_eq!(grid.map.len(), 43);
+        assert_eq!(grid.width(), 24);
+
+        let centre = grid.cell_at(&Position { x: 0, y: 0 }).unwrap();
+        assert_eq!(centre.neighbours.len(), 6);
+
+        let cell = grid.cell_at(&Position { x: 11, y: 2 }).unwrap();
+        assert_eq!(
+            cell.neighbours.get(&Direction::Clockwise),
+            Some(&Position { x: 0, y: 2 })
+        );
+        assert_eq!(
+            cell.neighbours.get(&Direction::Inward),
+            Some(&Position { x: 5, y: 1 })
+        );
+        assert_eq!(
+            cell.neighbours.get(&Direction::Outward(1)),
+            Some(&Position { x: 23, y: 3 })
+        );
+        assert!(grid.cell_at(&Position { x: 12, y: 2 }).is_none());
+    }
+
+    #[test]
+    fn should_generate_polar_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut grid: Box<dyn Grid> = Box::new(PolarGrid::new(5));
+        recursive_backtracker(&mut grid, &mut rng);
+
+        let reachable = distances(&Position { x: 0, y: 0 }, grid.links());
+        assert_eq!(reachable.len(), grid.map().len());
+
+        let svg = grid.draw(grid.map(), grid.width(), grid.height());
+        assert!(svg.starts_with("<svg viewBox=\"0 0 160 160\""));
+        assert!(svg.contains("<path d=\"M "));
+        assert!(svg.contains("<circle cx=\"80.00\" cy=\"80.00\" r=\"80.00\""));
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use crate::{
+    Direction, Position, Shape,
+    base_grid::{GridSetup, Svg},
+    cell::Cell,
+    distances::Distances,
+    grid::Grid,
+};
+
+// rows of triangles that alternate between pointing up and down. upright
+// cells share their base with the cell below, inverted ones with the cell above
+#[derive(Debug, Eq, PartialEq)]
+pub struct TriangleGrid {
+    pub map: Vec<Cell>,
+    pub width: i32,
+    pub height: i32,
+    pub links: HashMap<Position, Vec<Position>>,
+    pub distances: Option<Distances>,
+}
+
+impl TriangleGrid {
+    pub fn new(width: i32, height: i32) -> Self {
+        let mut map = Self::prepare_map(width, height);
+        Self::configure_cells(&mut map, width, height);
+
+        TriangleGrid {
+            map,
+            width,
+            height,
+            links: HashMap::new(),
+            distances: None,
+        }
+    }
+
+    pub fn is_upright(pos: &Position) -> bool {
+        (pos.x + pos.y) % 2 == 0
+    }
+}
+
+impl Grid for TriangleGrid {
+    fn map(&self) -> &Vec<Cell> {
+        &self.map
+    }
+    fn width(&self) -> i32 {
+        self.width
+    }
+    fn height(&self) -> i32 {
+        self.height
+    }
+    fn links(&self) -> &HashMap<Position, Vec<Position>> {
+        &self.links
+    }
+    fn links_mut(&mut self) -> &mut HashMap<Position, Vec<Position>> {
+        &mut self.links
+    }
+    fn set_links(&mut self, links: HashMap<Position, Vec<Position>>) {
+        self.links = links;
+    }
+    fn distances(&self) -> Option<&Distances> {
+        self.distances.as_ref()
+    }
+
+    fn set_distances(&mut self, distances: Distances) {
+        self.distances = Some(distances);
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::Triangle
+    }
+}
+
+impl GridSetup for TriangleGrid {
+    fn configure_cells(map: &mut Vec<Cell>, width: i32, height: i32) {
+        let in_grid = |pos: &Position| pos.x >= 0 && pos.y >= 0 && pos.x < width && pos.y < height;
+
+        for cell in map.iter_mut() {
+            let row = cell.position.y;
+            let col = cell.position.x;
+
+            // the third neighbour is across the flat side
+            let flat_side = if Self::is_upright(&cell.position) {
+                (Direction::South, Position { x: col, y: row + 1 })
+            } else {
+                (Direction::North, Position { x: col, y: row - 1 })
+            };
+
+            let neighbours = [
+                (Direction::West, Position { x: col - 1, y: row }),
+                (Direction::East, Position { x: col + 1, y: row }),
+                flat_side,
+            ];
+
+            for (direction, pos) in neighbours {
+                if in_grid(&pos) {
+                    cell.neighbours.insert(direction, pos);
+                }
+            }
+        }
+    }
+}
+
+impl Svg for TriangleGrid {}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        Direction, Position,
+        algos::recursive_backtracker::recursive_backtracker,
+        base_grid::{CELL_SIZE, Svg, joins, removed_walls, svg_walls},
+        distances::distances,
+        grid::Grid,
+    };
+
+    use super::TriangleGrid;
+
+    #[test]
+    fn it_should_find_three_neighbours() {
+        let grid = TriangleGrid::new(4, 3);
+
+        let upright = grid.cell_at(&Position { x: 1, y: 1 }).unwrap();
+        assert_eq!(upright.neighbours.len(), 3);
+        assert_eq!(
+            upright.neighbours.get(&Direction::South),
+            Some(&Position { x: 1, y: 2 })
+        );
+        assert_eq!(upright.neighbours.get(&Direction::North), None);
+
+        let inverted = grid.cell_at(&Position { x: 2, y: 1 }).unwrap();
+        assert_eq!(
+            inverted.neighbours.get(&Direction::North),
+            Some(&Position { x: 2, y: 0 })
+        );
+        assert_eq!(inverted.neighbours.get(&Direction::South), None);
+
+        // upright on the bottom row, so only has cells to either side
+        let edge = grid.cell_at(&Position { x: 2, y: 2 }).unwrap();
+        assert_eq!(edge.neighbours.len(), 2);
+    }
+
+    #[test]
+    fn should_generate_triangle_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut grid: Box<dyn Grid> = Box::new(TriangleGrid::new(7, 4));
+        recursive_backtracker(&mut grid, &mut rng);
+
+        let reachable = distances(&Position { x: 0, y: 0 }, grid.links());
+        assert_eq!(reachable.len(), 28);
+
+        let svg = grid.draw(grid.map(), grid.width(), grid.height());
+        assert!(svg.starts_with("<svg viewBox=\"0 0 64.00 55.43\""));
+    }
+
+    #[test]
+    fn it_should_open_linked_sides() {
+        let mut grid: Box<dyn Grid> = Box::new(TriangleGrid::new(3, 2));
+        let closed = grid.draw(grid.map(), grid.width(), grid.height());
+        // six triangles, with four slanted and two flat sides shared
+        assert_eq!(svg_walls(&closed).len(), 6 * 3 - 6);
+
+        // the top left cell is upright, so its east side slants and its
+        // base is the flat top of the inverted cell below
+        let corner = Position { x: 0, y: 0 };
+        grid.link(&corner, &Position { x: 1, y: 0 });
+        grid.link(&corner, &Position { x: 0, y: 1 });
+        let open = grid.draw(grid.map(), grid.width(), grid.height());
+        let removed = removed_walls(&closed, &open);
+
+        // the apex is at the top middle, the base a cell height below
+        let size = CELL_SIZE as f64;
+        let cell_height = size * 3.0_f64.sqrt() / 2.0;
+        let apex = (size / 2.0, 0.0);
+        let (base_west, base_east) = ((0.0, cell_height), (size, cell_height));
+        assert_eq!(removed.len(), 2);
+        assert!(removed.iter().any(|wall| joins(wall, base_east, apex)));
+        assert!(removed.iter().any(|wall| joins(wall, base_west, base_east)));
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use crate::{
+    Direction, Position, Shape,
+    base_grid::{GridSetup, Svg},
+    cell::Cell,
+    distances::Distances,
+    grid::Grid,
+};
+
+// octagons and squares. octagons are where x + y is even, and meet the
+// octagons diagonally next to them on their cut corners. the squares fill
+// the gaps, so only have the four octagons around them as neighbours
+#[derive(Debug, Eq, PartialEq)]
+pub struct UpsilonGrid {
+    pub map: Vec<Cell>,
+    pub width: i32,
+    pub height: i32,
+    pub links: HashMap<Position, Vec<Position>>,
+    pub distances: Option<Distances>,
+}
+
+impl UpsilonGrid {
+    pub fn new(width: i32, height: i32) -> Self {
+        let mut map = Self::prepare_map(width, height);
+        Self::configure_cells(&mut map, width, height);
+
+        UpsilonGrid {
+            map,
+            width,
+            height,
+            links: HashMap::new(),
+            distances: None,
+        }
+    }
+
+    pub fn is_octagon(pos: &Position) -> bool {
+        (pos.x + pos.y) % 2 == 0
+    }
+}
+
+impl Grid for UpsilonGrid {
+    fn map(&self) -> &Vec<Cell> {
+        &self.map
+    }
+    fn width(&self) -> i32 {
+        self.width
+    }
+    fn height(&self) -> i32 {
+        self.height
+    }
+    fn links(&self) -> &HashMap<Position, Vec<Position>> {
+        &self.links
+    }
+    fn links_mut(&mut self) -> &mut HashMap<Position, Vec<Position>> {
+        &mut self.links
+    }
+    fn set_links(&mut self, links: HashMap<Position, Vec<Position>>) {
+        self.links = links;
+    }
+    fn distances(&self) -> Option<&Distances> {
+        self.distances.as_ref()
+    }
+
+    fn set_distances(&mut self, distances: Distances) {
+        self.distances = Some(distances);
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::Upsilon
+    }
+}
+
+impl GridSetup for UpsilonGrid {
+    fn configure_cells(map: &mut Vec<Cell>, width: i32, height: i32) {
+        let in_grid = |pos: &Position| pos.x >= 0 && pos.y >= 0 && pos.x < width && pos.y < height;
+
+        for cell in map.iter_mut() {
+            let row = cell.position.y;
+            let col = cell.position.x;
+
+            let mut steps = vec![
+                (Direction::North, 0, -1),
+                (Direction::South, 0, 1),
+                (Direction::West, -1, 0),
+                (Direction::East, 1, 0),
+            ];
+            if Self::is_octagon(&cell.position) {
+                steps.extend([
+                    (Direction::NorthWest, -1, -1),
+                    (Direction::NorthEast, 1, -1),
+                    (Direction::SouthWest, -1, 1),
+                    (Direction::SouthEast, 1, 1),
+                ]);
+            }
+
+            for (direction, dx, dy) in steps {
+                let pos = Position {
+                    x: col + dx,
+                    y: row + dy,
+                };
+                if in_grid(&pos) {
+                    cell.neighbours.insert(direction, pos);
+                }
+            }
+        }
+    }
+}
+
+impl Svg for UpsilonGrid {}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        Direction, Position,
+        algos::recursive_backtracker::recursive_backtracker,
+        base_grid::{CELL_SIZE, Svg, joins, removed_walls, svg_walls},
+        distances::distances,
+        grid::Grid,
+    };
+
+    use super::UpsilonGrid;
+
+    #[test]
+    fn it_should_mix_octagons_and_squares() {
+        let grid = UpsilonGrid::new(4, 4);
+
+        let octagon = grid.cell_at(&Position { x: 1, y: 1 }).unwrap();
+        assert_eq!(octagon.neighbours.len(), 8);
+        assert_eq!(
+            octagon.neighbours.get(&Direction::SouthEast),
+            Some(&Position { x: 2, y: 2 })
+        );
+
+        let square = grid.cell_at(&Position { x: 2, y: 1 }).unwrap();
+        assert_eq!(square.neighbours.len(), 4);
+        assert_eq!(square.neighbours.get(&Direction::NorthEast), None);
+
+        let corner = grid.cell_at(&Position { x: 0, y: 0 }).unwrap();
+        assert_eq!(corner.neighbours.len(), 3);
+    }
+
+    #[test]
+    fn should_generate_upsilon_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut grid: Box<dyn Grid> = Box::new(UpsilonGrid::new(5, 5));
+        recursive_backtracker(&mut grid, &mut rng);
+
+        let reachable = distances(&Position { x: 0, y: 0 }, grid.links());
+        assert_eq!(reachable.len(), 25);
+
+        let svg = grid.draw(grid.map(), grid.width(), grid.height());
+        assert!(svg.starts_with("<svg viewBox=\"0 0 86.63 86.63\""));
+    }
+
+    #[test]
+    fn it_should_open_linked_sides() {
+        let mut grid: Box<dyn Grid> = Box::new(UpsilonGrid::new(3, 3));
+        let closed = grid.draw(grid.map(), grid.width(), grid.height());
+        // five octagons and four squares. each square shares a side with
+        // three octagons, and the middle octagon meets the four in the corners
+        assert_eq!(svg_walls(&closed).len(), 5 * 8 + 4 * 4 - 4 * 3 - 4);
+
+        // from the middle octagon, across a cut corner and into a square
+        let middle = Position { x: 1, y: 1 };
+        grid.link(&middle, &Position { x: 2, y: 2 });
+        grid.link(&middle, &Position { x: 1, y: 0 });
+        let open = grid.draw(grid.map(), grid.width(), grid.height());
+        let removed = removed_walls(&closed, &open);
+
+        // the octagon's short diagonal side, and the straight side on top
+        // that's as long as the square's
+        let size = CELL_SIZE as f64;
+        let half_octagon = size * 2.0_f64.sqrt() / 2.0;
+        let half_side = size - half_octagon;
+        let (cx, cy) = (half_octagon + size, half_octagon + size);
+        let (h, q) = (half_octagon, half_side);
+        assert_eq!(removed.len(), 2);
+        assert!(
+            removed
+                .iter()
+                .any(|wall| joins(wall, (cx + h, cy + q), (cx + q, cy + h)))
+        );
+        assert!(
+            removed
+                .iter()
+                .any(|wall| joins(wall, (cx - q, cy - h), (cx + q, cy - h)))
+        );
+    }
+}
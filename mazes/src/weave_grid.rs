@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+use rand::{Rng, rngs::SmallRng, seq::SliceRandom};
+
+use crate::{
+    Direction, Position, Shape,
+    algos::link,
+    base_grid::{GridSetup, Svg},
+    cell::Cell,
+    distances::{Distances, distances},
+    grid::Grid,
+};
+
+// which way the passage on top of a crossing runs. the tunnel goes
+// underneath at right angles to it
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Passage {
+    Vertical,
+    Horizontal,
+}
+
+// a square grid where passages can cross. at a crossing the cell is linked
+// to the cells above and below it (or either side), and the tunnel is a
+// direct link between the two cells it runs between, so anything following
+// links goes straight underneath
+//
+// crossings are placed before the maze is generated. every generator starts
+// from the links already on a grid, but only kruskals counts linked cells as
+// connected. the others treat them as unvisited and carve into them again,
+// adding duplicate links and loops
+#[derive(Debug, Eq, PartialEq)]
+pub struct WeaveGrid {
+    pub map: Vec<Cell>,
+    pub width: i32,
+    pub height: i32,
+    pub links: HashMap<Position, Vec<Position>>,
+    pub distances: Option<Distances>,
+    pub crossings: Vec<(Position, Passage)>,
+}
+
+impl WeaveGrid {
+    pub fn new(width: i32, height: i32) -> Self {
+        let mut map = Self::prepare_map(width, height);
+        Self::configure_cells(&mut map, width, height);
+
+        WeaveGrid {
+            map,
+            width,
+            height,
+            links: HashMap::new(),
+            distances: None,
+            crossings: vec![],
+        }
+    }
+
+    // adds a crossing at `pos`, returning false if there isn't room for one.
+    // the cell needs four neighbours, none of them crossings, and no links yet
+    pub fn add_crossing(&mut self, pos: &Position, over: Passage) -> bool {
+        let Some(cell) = self.cell_at(pos) else {
+            return false;
+        };
+
+        let (over_dirs, under_dirs) = match over {
+            Passage::Vertical => (
+                (Direction::North, Direction::South),
+                (Direction::West, Direction::East),
+            ),
+            Passage::Horizontal => (
+                (Direction::West, Direction::East),
+                (Direction::North, Direction::South),
+            ),
+        };
+
+        let neighbour = |direction: &Direction| cell.neighbours.get(direction).copied();
+        let (Some(over_start), Some(over_end), Some(under_start), Some(under_end)) = (
+            neighbour(&over_dirs.0),
+            neighbour(&over_dirs.1),
+            neighbour(&under_dirs.0),
+            neighbour(&under_dirs.1),
+        ) else {
+            return false;
+        };
+
+        let sides = [over_start, over_end, under_start, under_end];
+        if self.links.contains_key(pos)
+            || sides
+                .iter()
+                .any(|side| self.crossings.iter().any(|(crossing, _)| crossing == side))
+        {
+            return false;
+        }
+
+        // both passages have to be separate already, or the crossing would
+        // close a loop
+        let from_over = distances(&over_start, &self.links);
+        if from_over.contains(&over_end)
+            || from_over.contains(&under_start)
+            || from_over.contains(&under_end)
+            || distances(&under_start, &self.links).contains(&under_end)
+        {
+            return false;
+        }
+
+        link(&mut self.links, &over_start, pos);
+        link(&mut self.links, pos, &over_end);
+        link(&mut self.links, &under_start, &under_end);
+
+        // the tunnel ends become each other's neighbours, and the crossing
+        // only has the cells on its own passage
+        let width = self.width;
+        let index = |pos: &Position| (pos.y * width + pos.x) as usize;
+        self.map[index(pos)].neighbours.remove(&under_dirs.0);
+        self.map[index(pos)].neighbours.remove(&under_dirs.1);
+        self.map[index(&under_start)]
+            .neighbours
+            .insert(under_dirs.1, under_end);
+        self.map[index(&under_end)]
+            .neighbours
+            .insert(under_dirs.0, under_start);
+
+        self.crossings.push((*pos, over));
+        true
+    }
+
+    // tries cells in a random order until `count` crossings are placed or
+    // there's nowhere left. returns how many were added
+    pub fn add_random_crossings(&mut self, rng: &mut SmallRng, count: usize) -> usize {
+        let mut candidates: Vec<Position> = self.map.iter().map(|cell| cell.position).collect();
+        candidates.shuffle(rng);
+
+        let mut added = 0;
+        for pos in candidates {
+            if added == count {
+                break;
+            }
+            let over = if rng.random_bool(0.5) {
+                Passage::Vertical
+            } else {
+                Passage::Horizontal
+            };
+            if self.add_crossing(&pos, over) {
+                added += 1;
+            }
+        }
+        added
+    }
+}
+
+impl Grid for WeaveGrid {
+    fn map(&self) -> &Vec<Cell> {
+        &self.map
+    }
+    fn width(&self) -> i32 {
+        self.width
+    }
+    fn height(&self) -> i32 {
+        self.height
+    }
+    fn links(&self) -> &HashMap<Position, Vec<Position>> {
+        &self.links
+    }
+    fn links_mut(&mut self) -> &mut HashMap<Position, Vec<Position>> {
+        &mut self.links
+    }
+    fn set_links(&mut self, links: HashMap<Position, Vec<Position>>) {
+        self.links = links;
+    }
+    fn distances(&self) -> Option<&Distances> {
+        self.distances.as_ref()
+    }
+
+    fn set_distances(&mut self, distances: Distances) {
+        self.distances = Some(distances);
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::Weave
+    }
+}
+
+impl GridSetup for WeaveGrid {}
+
+impl Svg for WeaveGrid {}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+    use rand_seeder::Seeder;
+
+    use crate::{
+        Direction, Position, algos::kruskals::kruskals, base_grid::Svg, distances::distances,
+        grid::Grid,
+    };
+
+    use super::{Passage, WeaveGrid};
+
+    #[test]
+    fn it_should_tunnel_under_crossings() {
+        let mut grid = WeaveGrid::new(3, 3);
+        let centre = Position { x: 1, y: 1 };
+        assert!(grid.add_crossing(&centre, Passage::Vertical));
+        // no room for another next to it, or on the edge
+        assert!(!grid.add_crossing(&Position { x: 1, y: 0 }, Passage::Horizontal));
+
+        assert!(grid.is_linked(&Position { x: 1, y: 0 }, &centre));
+        assert!(grid.is_linked(&centre, &Position { x: 1, y: 2 }));
+        assert!(grid.is_linked(&Position { x: 0, y: 1 }, &Position { x: 2, y: 1 }));
+        assert!(!grid.is_linked(&Position { x: 0, y: 1 }, &centre));
+
+        let west = grid.cell_at(&Position { x: 0, y: 1 }).unwrap();
+        assert_eq!(
+            west.neighbours.get(&Direction::East),
+            Some(&Position { x: 2, y: 1 })
+        );
+        let crossing = grid.cell_at(&centre).unwrap();
+        assert_eq!(crossing.neighbours.get(&Direction::East), None);
+
+        // straight under the crossing, rather than round it
+        let from_west = distances(&Position { x: 0, y: 1 }, grid.links());
+        assert_eq!(from_west.get(&Position { x: 2, y: 1 }), Some(1));
+    }
+
+    #[test]
+    fn should_generate_weave_maze() {
+        let seed = "abc12345abc";
+        let mut rng: SmallRng = Seeder::from(&seed).into_rng();
+        let mut weave = WeaveGrid::new(6, 6);
+        assert_eq!(weave.add_random_crossings(&mut rng, 4), 4);
+
+        let mut grid: Box<dyn Grid> = Box::new(weave);
+        kruskals(&mut grid, &mut rng);
+
+        // a perfect maze still has one fewer passage than cells, the
+        // crossing cells just count their passage through the middle
+        let link_count: usize = grid.links().values().map(|links| links.len()).sum();
+        assert_eq!(link_count, 2 * 35);
+        let reachable = distances(&Position { x: 0, y: 0 }, grid.links());
+        assert_eq!(reachable.len(), 36);
+
+        let svg = grid.draw(grid.map(), grid.width(), grid.height());
+        assert!(svg.starts_with("<svg viewBox=\"0 0 96 96\""));
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use crate::Position;
+
+// how much it costs to move into each cell (lava, mud, water). kept apart
+// from the grid so any shape can be weighted. cells without a weight cost 1,
+// so with none set, weighted distances are the same as counting steps
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Weights {
+    cells: HashMap<Position, i32>,
+}
+
+impl Weights {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // negative weights would let a path get shorter the longer it goes on,
+    // so they're treated as free
+    pub fn set(&mut self, pos: &Position, weight: i32) {
+        self.cells.insert(*pos, weight.max(0));
+    }
+
+    pub fn get(&self, pos: &Position) -> i32 {
+        self.cells.get(pos).copied().unwrap_or(1)
+    }
+
+    // the cheapest any cell can be, so estimates in steps can be scaled down
+    // to never cost more than the real path
+    pub fn lightest(&self) -> i32 {
+        self.cells.values().copied().fold(1, i32::min)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Position;
+
+    use super::Weights;
+
+    #[test]
+    fn it_should_weigh_cells() {
+        let mut weights = Weights::new();
+        let mud = Position { x: 1, y: 1 };
+        weights.set(&mud, 5);
+        weights.set(&Position { x: 2, y: 2 }, -3);
+
+        assert_eq!(weights.get(&mud), 5);
+        assert_eq!(weights.get(&Position { x: 0, y: 0 }), 1);
+        assert_eq!(weights.get(&Position { x: 2, y: 2 }), 0);
+        assert_eq!(weights.lightest(), 0);
+        assert!(!weights.is_empty());
+        assert!(Weights::new().is_empty());
+        assert_eq!(Weights::new().lightest(), 1);
+    }
+}
@@ -1,36 +1,100 @@
-use clap::Parser;
+use clap::{Parser, builder::PossibleValuesParser};
 use serde::Serialize;
 use std::{
+    error::Error,
     fs::write,
-    io,
+    path::PathBuf,
     time::{SystemTime, UNIX_EPOCH},
 };
 
 use mazes::{
-    Position,
-    algos::{binary_tree::binary_tree, sidewinder::sidewinder},
+    Position, Shape, Topology,
+    algos::{GENERATORS, Options, find, growing_tree::Strategy},
     base_grid::Svg,
-    distances::path_to,
+    distances::{Distances, longest_weighted_path},
     djikstra::DjikstraGrid,
+    graph_grid::GraphGrid,
     grid::{Grid, StandardGrid},
+    grid_3d::Grid3D,
+    hex_grid::HexGrid,
+    mask::Mask,
+    masked_grid::MaskedGrid,
+    polar_grid::PolarGrid,
+    triangle_grid::TriangleGrid,
+    upsilon_grid::UpsilonGrid,
+    weave_grid::WeaveGrid,
+    weights::Weights,
 };
 use rand::rngs::SmallRng;
 use rand_seeder::Seeder;
 
-#[derive(clap::ValueEnum, Clone, Default, Debug, Serialize)]
-#[serde(rename_all = "kebab-case")]
-enum Algos {
-    Sidewinder,
-    #[default]
-    Binary,
-}
-
 #[derive(clap::ValueEnum, Clone, Default, Debug, Serialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 enum GridType {
     #[default]
     Standard,
     Djikstra,
+    // uses height as the number of rings
+    Polar,
+    Hex,
+    Triangle,
+    // passages can cross over each other. only kruskals builds around the
+    // crossings without carving loops through them
+    Weave,
+    // several floors joined by stairs, see --levels
+    Levels,
+    Upsilon,
+    // width x height random cells in a width x height area
+    Voronoi,
+}
+
+// mirrors mazes::Topology so clap can parse it
+#[derive(clap::ValueEnum, Clone, Copy, Default, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+enum TopologyArg {
+    #[default]
+    Plane,
+    Cylinder,
+    Torus,
+    Mobius,
+    Klein,
+}
+
+impl From<TopologyArg> for Topology {
+    fn from(topology: TopologyArg) -> Self {
+        match topology {
+            TopologyArg::Plane => Topology::Plane,
+            TopologyArg::Cylinder => Topology::Cylinder,
+            TopologyArg::Torus => Topology::Torus,
+            TopologyArg::Mobius => Topology::Mobius,
+            TopologyArg::Klein => Topology::Klein,
+        }
+    }
+}
+
+// binary is the quickest, but only works on a whole, unwrapped rectangle of
+// square cells
+fn default_algo(grid: &dyn Grid) -> &'static str {
+    match grid.shape() {
+        Shape::Weave => "kruskals",
+        _ if find("binary").is_some_and(|binary| binary.supports(grid)) => "binary",
+        _ => "recursive-backtracker",
+    }
+}
+
+// a cell and its weight, as `x,y=weight`
+fn parse_weight(arg: &str) -> Result<(Position, i32), String> {
+    let invalid = || format!("expected x,y=weight, not '{}'", arg);
+    let (cell, weight) = arg.split_once('=').ok_or_else(invalid)?;
+    let (x, y) = cell.split_once(',').ok_or_else(invalid)?;
+    let parse = |num: &str| num.trim().parse::<i32>().map_err(|_| invalid());
+    Ok((
+        Position {
+            x: parse(x)?,
+            y: parse(y)?,
+        },
+        parse(weight)?,
+    ))
 }
 
 #[derive(Parser)]
@@ -43,43 +107,168 @@ struct GenerateArgs {
     seed: String,
     #[arg(short, long, default_value_t, value_enum)]
     grid: GridType,
+    /// Which edges of a standard grid join up
     #[arg(short, long, default_value_t, value_enum)]
-    algo: Algos,
+    topology: TopologyArg,
+    /// Defaults to binary for plane square grids without a mask, kruskals for
+    /// weave grids and recursive-backtracker for everything else
+    #[arg(short, long,
+        value_parser = PossibleValuesParser::new(GENERATORS.iter().map(|generator| generator.name)))]
+    algo: Option<String>,
+    /// List the available algorithms and exit
+    #[arg(long)]
+    list_algos: bool,
+    /// Cell selection for growing-tree, eg `newest` or `newest:0.5,random:0.5`
+    #[arg(long)]
+    strategy: Option<Strategy>,
+    /// Largest room recursive-division may leave open
+    #[arg(long)]
+    room_size: Option<i32>,
+    /// How many crossings to put in a weave maze. Defaults to one per five cells
+    #[arg(long)]
+    crossings: Option<usize>,
+    /// How many floors a levels maze has
+    #[arg(long, default_value_t = 2)]
+    levels: usize,
+    /// Shape the maze with a mask: a text file with `X` for cells to leave out,
+    /// or a black and white PNG. Overrides the grid type and size
+    #[arg(short, long)]
+    mask: Option<PathBuf>,
+    /// Make a cell of a djikstra grid cost more to walk through, eg `3,4=10`.
+    /// Can be given more than once
+    #[arg(short = 'w', long = "weight", value_parser = parse_weight)]
+    weights: Vec<(Position, i32)>,
     #[arg(short = 'x', long, default_value_t = 8)]
     width: usize,
     #[arg(short = 'y', long, default_value_t = 8)]
     height: usize,
 }
 
-fn main() -> Result<(), io::Error> {
+fn main() -> Result<(), Box<dyn Error>> {
     let gen_args = GenerateArgs::parse();
+    if gen_args.list_algos {
+        for generator in GENERATORS {
+            println!("{}", generator.name);
+            println!("    {}", generator.description);
+            println!("    Bias: {}", generator.bias);
+            println!("    Grids: {:?}", generator.shapes);
+        }
+        return Ok(());
+    }
+
     let mut rng: SmallRng = Seeder::from(&gen_args.seed).into_rng();
 
-    let mut grid: Box<dyn Grid> = match gen_args.grid {
-        GridType::Standard => Box::new(StandardGrid::new(
+    let mut weights = Weights::new();
+    for (pos, weight) in &gen_args.weights {
+        weights.set(pos, *weight);
+    }
+    if !weights.is_empty() && (gen_args.grid != GridType::Djikstra || gen_args.mask.is_some()) {
+        return Err("weights only work on djikstra grids".into());
+    }
+
+    let mut grid: Box<dyn Grid> = match (&gen_args.mask, &gen_args.grid) {
+        (Some(path), _) => {
+            let mask = match path.extension().and_then(|ext| ext.to_str()) {
+                Some("png") => Mask::from_png(path)?,
+                _ => Mask::from_txt(path)?,
+            };
+            Box::new(MaskedGrid::new(mask))
+        }
+        (None, GridType::Standard) => Box::new(StandardGrid::with_topology(
+            gen_args.width as i32,
+            gen_args.height as i32,
+            gen_args.topology.into(),
+        )),
+        (None, GridType::Djikstra) => {
+            let mut djikstra = DjikstraGrid::new(gen_args.width as i32, gen_args.height as i32);
+            djikstra.set_weights(weights.clone());
+            Box::new(djikstra)
+        }
+        (None, GridType::Polar) => Box::new(PolarGrid::new(gen_args.height as i32)),
+        (None, GridType::Hex) => {
+            Box::new(HexGrid::new(gen_args.width as i32, gen_args.height as i32))
+        }
+        (None, GridType::Triangle) => Box::new(TriangleGrid::new(
             gen_args.width as i32,
             gen_args.height as i32,
         )),
-        GridType::Djikstra => Box::new(DjikstraGrid::new(
+        (None, GridType::Upsilon) => Box::new(UpsilonGrid::new(
             gen_args.width as i32,
             gen_args.height as i32,
         )),
+        (None, GridType::Voronoi) => Box::new(GraphGrid::voronoi(
+            gen_args.width as i32,
+            gen_args.height as i32,
+            gen_args.width * gen_args.height,
+            &mut rng,
+        )),
+        (None, GridType::Levels) => Box::new(Grid3D::new(
+            gen_args.width as i32,
+            gen_args.height as i32,
+            gen_args.levels as i32,
+        )),
+        (None, GridType::Weave) => {
+            let mut weave = WeaveGrid::new(gen_args.width as i32, gen_args.height as i32);
+            let crossings = gen_args
+                .crossings
+                .unwrap_or(gen_args.width * gen_args.height / 5);
+            weave.add_random_crossings(&mut rng, crossings);
+            Box::new(weave)
+        }
     };
 
-    match gen_args.algo {
-        Algos::Sidewinder => sidewinder(&mut grid, &mut rng),
-        Algos::Binary => binary_tree(&mut grid, &mut rng),
+    let name = gen_args
+        .algo
+        .as_deref()
+        .unwrap_or_else(|| default_algo(grid.as_ref()));
+    let generator = find(name).expect("Unknown algorithm");
+    if !generator.supports(grid.as_ref()) {
+        let usable: Vec<&str> = GENERATORS
+            .iter()
+            .filter(|generator| generator.supports(grid.as_ref()))
+            .map(|generator| generator.name)
+            .collect();
+        let kind = match (grid.masked(), grid.topology()) {
+            (true, _) => format!("masked {:?}", grid.shape()),
+            (false, Topology::Plane) => format!("{:?}", grid.shape()),
+            (false, topology) => format!("{:?} {:?}", topology, grid.shape()),
+        };
+        return Err(format!(
+            "{} doesn't work on {} grids, try one of: {}",
+            name,
+            kind,
+            usable.join(", ")
+        )
+        .into());
+    }
+    let options = Options {
+        strategy: gen_args.strategy,
+        room_size: gen_args.room_size,
     };
+    generator.generate_with(&mut grid, &mut rng, &options);
 
     if gen_args.grid == GridType::Djikstra {
-        // find a path and update grid distances
-        // only works for djikstra
-        let path = path_to(&Position { x: 5, y: 1 }, &grid);
-        grid.set_distances(path);
+        // put the entrance and exit as far apart as they can be, and show
+        // the route between them. only djikstra grids draw distances
+        if let Some(longest) = longest_weighted_path(grid.as_ref(), &weights) {
+            println!(
+                "Entrance: {:?}, exit: {:?}, {} cells apart, costing {}",
+                longest.start,
+                longest.goal,
+                longest.path.len() - 1,
+                longest.cost
+            );
+            if let Some(route) = Distances::along(&longest.path) {
+                grid.set_distances(route);
+            }
+        }
+    }
+    // ascii only makes sense for square cells
+    if matches!(grid.shape(), Shape::Square | Shape::Levels) {
+        println!("{}", grid);
     }
-    println!("{}", grid);
 
-    let output = grid.draw(&grid.map(), grid.width(), grid.height());
+    let output = grid.draw(grid.map(), grid.width(), grid.height());
     write("./test.svg", output)?;
 
     Ok(())